@@ -0,0 +1,63 @@
+use std::str::FromStr;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_iam::aws::{AwsEngine, WildString};
+use rust_iam::{Effect, EvaluationMode, MaybeEffect, PolicyCollection, ResourceAbstract, Statement};
+
+/// Builds an allow-only collection with `count` statements, where the first one matches
+/// every request used below.
+fn allow_only_collection(count: usize) -> PolicyCollection<AwsEngine> {
+    let statements: Vec<String> = (0..count)
+        .map(|i| {
+            let actions = if i == 0 { "s3:*".to_string() } else { format!("service{i}:Get*") };
+            format!(r#"{{"effect": "allow", "actions": ["{actions}"], "resources": ["arn:aws:s3:::bucket-{i}/*", "arn:aws:s3:::bucket/*"]}}"#)
+        })
+        .collect();
+    serde_json::from_str(&format!(r#"[{{"statements": [{}]}}]"#, statements.join(","))).unwrap()
+}
+
+fn bench_allow_only(c: &mut Criterion) {
+    let action = WildString("s3:GetObject".to_string());
+    let resource = ResourceAbstract::<AwsEngine>::from_str("arn:aws:s3:::bucket/key").unwrap();
+
+    for count in [10, 1000] {
+        let policies = allow_only_collection(count);
+        let mut group = c.benchmark_group(format!("allow_only_{count}"));
+        group.bench_function("full_scan", |b| {
+            b.iter(|| {
+                policies
+                    .statements()
+                    .filter(|(_, statement)| statement.effect == Effect::Allow)
+                    .fold(false, |allowed, (_, statement)| {
+                        allowed | (statement.matches(black_box(&action), black_box(&resource)) == MaybeEffect::Allow)
+                    })
+            })
+        });
+        group.bench_function("early_exit", |b| {
+            b.iter(|| policies.validate_with_mode(black_box(&action), black_box(&resource), EvaluationMode::AllowOnly))
+        });
+        group.finish();
+    }
+}
+
+fn bench_prepared_statement(c: &mut Criterion) {
+    let statement: Statement<AwsEngine> = serde_json::from_str(r#"{
+        "effect": "allow",
+        "actions": ["s3:Get?bject*", "s3:*Object*", "s3:List*"],
+        "resources": ["arn:aws:s3:::*-logs-*/*.log", "arn:aws:s3:::bucket/*"]
+    }"#).unwrap();
+    let prepared = statement.prepare();
+    let action = WildString("s3:GetObjectVersion".to_string());
+    let resource = ResourceAbstract::<AwsEngine>::from_str("arn:aws:s3:::app-logs-2024/01/01/app.log").unwrap();
+
+    let mut group = c.benchmark_group("prepared_statement");
+    group.bench_function("statement", |b| {
+        b.iter(|| statement.matches(black_box(&action), black_box(&resource)))
+    });
+    group.bench_function("prepared", |b| {
+        b.iter(|| prepared.matches(black_box(&action), black_box(&resource)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_allow_only, bench_prepared_statement);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_iam::aws::WildString;
+use rust_iam::traits::MatchesTrait;
+use wildcard::Wildcard;
+
+/// Common IAM pattern shapes paired with a value they are checked against.
+const CASES: &[(&str, &str, &str)] = &[
+    ("action_service_wildcard", "s3:*", "s3:GetObject"),
+    ("action_prefix_wildcard", "s3:Get*", "s3:GetObjectVersion"),
+    ("arn_object_wildcard", "arn:aws:s3:::bucket/*", "arn:aws:s3:::bucket/logs/2024/01/01/app.log"),
+    ("arn_miss", "arn:aws:s3:::bucket/*", "arn:aws:s3:::other-bucket/logs/app.log"),
+    ("arn_infix_wildcard", "arn:aws:s3:::bucket/*.log", "arn:aws:s3:::bucket/logs/2024/01/01/app.log"),
+    ("arn_multi_wildcard", "arn:aws:s3:::*-logs-*", "arn:aws:s3:::app-logs-2024"),
+];
+
+/// A hand-written matcher that only understands a single `*` (trailing or internal),
+/// falling back to equality for literal patterns.
+fn prefix_match(pattern: &str, value: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            value.len() >= prefix.len() + suffix.len() && value.starts_with(prefix) && value.ends_with(suffix)
+        }
+        None => pattern == value,
+    }
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    Wildcard::new(pattern.as_bytes())
+        .map(|w| w.is_match(value.as_bytes()))
+        .unwrap_or(false)
+}
+
+fn bench_backends(c: &mut Criterion) {
+    for (name, pattern, value) in CASES {
+        let mut group = c.benchmark_group(*name);
+        group.bench_function("wildcard", |b| {
+            b.iter(|| wildcard_match(black_box(pattern), black_box(value)))
+        });
+        if pattern.matches('*').count() == 1 {
+            group.bench_function("prefix", |b| {
+                b.iter(|| prefix_match(black_box(pattern), black_box(value)))
+            });
+        }
+        group.finish();
+    }
+}
+
+fn bench_wild_string(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wild_string");
+    for (name, pattern, value) in CASES {
+        let pattern = WildString(pattern.to_string());
+        let value = WildString(value.to_string());
+        group.bench_function(*name, |b| {
+            b.iter(|| black_box(&pattern).matches(black_box(&value)))
+        });
+    }
+    group.finish();
+}
+
+/// Re-checks the same action strings against a policy-sized set of patterns, as an evaluation
+/// loop does across statements. Run with and without `--features match-cache` to compare.
+fn bench_repeated_checks(c: &mut Criterion) {
+    let patterns: Vec<WildString> = (0..50)
+        .map(|i| match i % 3 {
+            0 => format!("service{i}:Get*"),
+            1 => format!("service{i}:*Object"),
+            _ => format!("s3:*Object{i}*"),
+        })
+        .map(WildString)
+        .collect();
+    let actions: Vec<WildString> = ["s3:GetObject", "s3:PutObject", "service1:DeleteObject", "service3:GetBucketAcl"]
+        .iter()
+        .map(|action| WildString(action.to_string()))
+        .collect();
+    c.bench_function("repeated_checks", |b| {
+        b.iter(|| {
+            actions
+                .iter()
+                .flat_map(|action| patterns.iter().map(move |pattern| (pattern, action)))
+                .filter(|(pattern, action)| black_box(pattern).matches(black_box(action)) == Ok(true))
+                .count()
+        })
+    });
+}
+
+criterion_group!(benches, bench_backends, bench_wild_string, bench_repeated_checks);
+criterion_main!(benches);
@@ -0,0 +1,81 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rust_iam::aws::AwsEngine;
+use rust_iam::traits::MatchesTrait;
+use rust_iam::{ResourceAbstract, ResourceRef};
+
+/// Counts every allocation made by the bench binary.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const PATTERN: &str = "arn:aws:s3:::bucket/logs/*";
+const REQUEST: &str = "arn:aws:ec2:us-east-1:123456789012:instance:i-0abcd1234efgh5678";
+const OBJECT: &str = "arn:aws:s3:::bucket/logs/2024/01/01/app.log";
+
+fn allocations_during<R>(f: impl FnOnce() -> R) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn report_allocations() {
+    let owned_pattern = ResourceAbstract::<AwsEngine>::from_str(PATTERN).unwrap();
+    let ref_pattern = ResourceRef::<AwsEngine>::parse(PATTERN).unwrap();
+    println!(
+        "allocations per parse: owned = {}, ref = {}",
+        allocations_during(|| ResourceAbstract::<AwsEngine>::from_str(REQUEST).unwrap()),
+        allocations_during(|| ResourceRef::<AwsEngine>::parse(REQUEST).unwrap()),
+    );
+    println!(
+        "allocations per parse + match: owned = {}, ref = {}",
+        allocations_during(|| owned_pattern.matches(&ResourceAbstract::from_str(OBJECT).unwrap())),
+        allocations_during(|| ref_pattern.matches(&ResourceRef::parse(OBJECT).unwrap())),
+    );
+}
+
+fn bench_parse(c: &mut Criterion) {
+    report_allocations();
+
+    let mut group = c.benchmark_group("arn_parse");
+    group.bench_function("owned", |b| {
+        b.iter(|| ResourceAbstract::<AwsEngine>::from_str(black_box(REQUEST)))
+    });
+    group.bench_function("ref", |b| {
+        b.iter(|| ResourceRef::<AwsEngine>::parse(black_box(REQUEST)))
+    });
+    group.finish();
+}
+
+fn bench_parse_and_match(c: &mut Criterion) {
+    let owned_pattern = ResourceAbstract::<AwsEngine>::from_str(PATTERN).unwrap();
+    let ref_pattern = ResourceRef::<AwsEngine>::parse(PATTERN).unwrap();
+
+    let mut group = c.benchmark_group("arn_parse_and_match");
+    group.bench_function("owned", |b| {
+        b.iter(|| owned_pattern.matches(&ResourceAbstract::from_str(black_box(OBJECT)).unwrap()))
+    });
+    group.bench_function("ref", |b| {
+        b.iter(|| ref_pattern.matches(&ResourceRef::parse(black_box(OBJECT)).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_parse_and_match);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! Evaluates an action on a resource against a policy file.
+//!
+//! ```text
+//! cargo run --example evaluate -- <policy.json> <action> <resource-arn>
+//! ```
+//!
+//! The policy file may contain a single policy object or a list of policies.
+//! The decision is printed first, followed by the statements that matched.
+
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use rust_iam::aws::{AwsEngine, WildString};
+use rust_iam::{MaybeEffect, Policy, PolicyCollection, ResourceAbstract};
+
+fn load_policies(path: &str) -> Result<PolicyCollection<AwsEngine>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("failed to read {path}: {e}"))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|e| format!("{path} is not valid JSON: {e}"))?;
+    if value.is_array() {
+        serde_json::from_value(value).map_err(|e| format!("invalid policy list in {path}: {e}"))
+    } else {
+        serde_json::from_value::<Policy<AwsEngine>>(value)
+            .map(|policy| PolicyCollection(vec![policy]))
+            .map_err(|e| format!("invalid policy in {path}: {e}"))
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let policies = load_policies(&args[1])?;
+    let action = WildString::from_str(&args[2]).map_err(|e| format!("invalid action {:?}: {e}", args[2]))?;
+    let resource = ResourceAbstract::<AwsEngine>::from_str(&args[3])
+        .map_err(|e| format!("invalid resource {:?}: {e}", args[3]))?;
+
+    let allowed = policies.validate(&action, &resource);
+    println!("{}", if allowed { "allow" } else { "deny" });
+
+    let mut matched = false;
+    for (p, policy) in policies.iter().enumerate() {
+        let name = policy.name.clone().unwrap_or_else(|| format!("policy #{p}"));
+        for (s, statement) in policy.statements.iter().enumerate() {
+            let effect = match statement.matches(&action, &resource) {
+                MaybeEffect::Allow => "allows",
+                MaybeEffect::Deny => "denies",
+                MaybeEffect::NotSpecified => continue,
+            };
+            matched = true;
+            println!("  {name}, statement #{s} {effect} {} on {}", args[2], args[3]);
+        }
+    }
+    if !matched {
+        println!("  no statement matched; denied implicitly");
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 4 {
+        eprintln!("usage: {} <policy.json> <action> <resource-arn>", args[0]);
+        return ExitCode::from(2);
+    }
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -26,15 +26,9 @@ pub fn derive_matches(input: TokenStream) -> TokenStream {
         quote! {
             impl MatchesTrait<bool> for #name {
                 fn matches(&self, value: &Self) -> Result<bool, &'static str> {
-                    use wildcard::Wildcard;
-
-                    let self_str = #func(self);
-                    let value_str = #func(value);
-
-                    let pattern = Wildcard::new(self_str.as_bytes())
-                        .map_err(|_| "Failed to compile wildcard pattern")?;
-
-                    Ok(pattern.is_match(value_str.as_bytes()))
+                    // Goes through the crate's segment matcher so the literal, `prefix*` and
+                    // `prefix*suffix` fast paths are shared with every other matcher.
+                    ::rust_iam::__private::glob(&#func(self), &#func(value))
                 }
             }
         }
@@ -0,0 +1,44 @@
+use crate::ResourceAbstract;
+use crate::engine::EngineTrait;
+
+/// A finite set of requests to evaluate policies over.
+///
+/// Policies match wildcard patterns, so the set of requests they cover is usually infinite.
+/// An `AccessUniverse` fixes the concrete actions and resources that matter (for example,
+/// every action a service exposes and every resource an account owns), so that questions such
+/// as "do these two policies grant the same access?" can be answered by exhaustive evaluation.
+///
+/// # Type Parameters
+/// - `Engine`: The engine that defines the action and resource types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessUniverse<Engine: EngineTrait> {
+    /// The candidate actions.
+    pub actions: Vec<Engine::Action>,
+
+    /// The candidate resources.
+    pub resources: Vec<ResourceAbstract<Engine>>,
+}
+
+impl<Engine: EngineTrait> AccessUniverse<Engine> {
+    /// Creates a universe from the given actions and resources.
+    pub fn new(actions: Vec<Engine::Action>, resources: Vec<ResourceAbstract<Engine>>) -> Self {
+        AccessUniverse { actions, resources }
+    }
+
+    /// Iterates over every `(action, resource)` pair, ordered by action and then by resource.
+    pub fn pairs(&self) -> impl Iterator<Item = (&Engine::Action, &ResourceAbstract<Engine>)> {
+        self.actions
+            .iter()
+            .flat_map(move |action| self.resources.iter().map(move |resource| (action, resource)))
+    }
+
+    /// Returns the number of `(action, resource)` pairs in the universe.
+    pub fn len(&self) -> usize {
+        self.actions.len() * self.resources.len()
+    }
+
+    /// Returns `true` if the universe contains no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
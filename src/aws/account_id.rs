@@ -0,0 +1,131 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use crate::traits::MatchesTrait;
+use super::WildString;
+
+/// An account id pattern that understands numeric ranges in addition to globs.
+///
+/// This is meant for custom engines whose account ids are numeric; AWS policies themselves only
+/// support globs in this segment. Two pattern forms are accepted:
+/// - A glob, matched like [`WildString`], e.g. `123456789012`, `12345678901*` or `*`.
+/// - An inclusive numeric range in brackets, e.g. `[100000000000-199999999999]`. It matches any
+///   account id that is made of digits only and falls within the bounds.
+///
+/// # Examples
+/// ```
+/// use rust_iam::aws::RangedAccountId;
+/// use rust_iam::traits::MatchesTrait;
+///
+/// let pattern: RangedAccountId = "[100000000000-199999999999]".parse().unwrap();
+/// assert_eq!(pattern.matches(&"123456789012".parse().unwrap()), Ok(true));
+/// assert_eq!(pattern.matches(&"223456789012".parse().unwrap()), Ok(false));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangedAccountId {
+    text: String,
+    range: Option<RangeInclusive<u64>>,
+}
+
+impl RangedAccountId {
+    /// Returns the numeric range if this is a `[low-high]` pattern.
+    pub fn range(&self) -> Option<&RangeInclusive<u64>> {
+        self.range.as_ref()
+    }
+}
+
+fn parse_range(s: &str) -> Result<RangeInclusive<u64>, &'static str> {
+    let inner = s
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or("Invalid account id range")?;
+    let (low, high) = inner.split_once('-').ok_or("Invalid account id range")?;
+    let bound = |b: &str| {
+        if b.is_empty() || !b.bytes().all(|c| c.is_ascii_digit()) {
+            return Err("Invalid account id range");
+        }
+        b.parse::<u64>().map_err(|_| "Invalid account id range")
+    };
+    let (low, high) = (bound(low)?, bound(high)?);
+    if low > high {
+        return Err("Invalid account id range");
+    }
+    Ok(low..=high)
+}
+
+impl FromStr for RangedAccountId {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = if s.starts_with('[') { Some(parse_range(s)?) } else { None };
+        Ok(RangedAccountId { text: s.to_string(), range })
+    }
+}
+
+impl fmt::Display for RangedAccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl MatchesTrait<bool> for RangedAccountId {
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        match &self.range {
+            Some(range) => Ok(value.range.is_none()
+                && !value.text.is_empty()
+                && value.text.bytes().all(|c| c.is_ascii_digit())
+                && value.text.parse::<u64>().is_ok_and(|id| range.contains(&id))),
+            None => WildString(self.text.clone()).matches(&WildString(value.text.clone())),
+        }
+    }
+}
+
+impl Serialize for RangedAccountId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for RangedAccountId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        RangedAccountId::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(s: &str) -> RangedAccountId {
+        RangedAccountId::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_prefix_patterns() {
+        assert_eq!(id("12345678901*").matches(&id("123456789012")), Ok(true));
+        assert_eq!(id("12345678901*").matches(&id("123456789022")), Ok(false));
+        assert_eq!(id("123456789012").matches(&id("123456789012")), Ok(true));
+        assert_eq!(id("*").matches(&id("999999999999")), Ok(true));
+    }
+
+    #[test]
+    fn test_range_patterns() {
+        let range = id("[100000000000-199999999999]");
+        assert_eq!(range.range(), Some(&(100000000000..=199999999999)));
+        assert_eq!(range.matches(&id("100000000000")), Ok(true));
+        assert_eq!(range.matches(&id("199999999999")), Ok(true));
+        assert_eq!(range.matches(&id("123456789012")), Ok(true));
+        assert_eq!(range.matches(&id("200000000000")), Ok(false));
+        assert_eq!(range.matches(&id("099999999999")), Ok(false));
+        assert_eq!(range.matches(&id("12345678901a")), Ok(false));
+        assert_eq!(range.to_string(), "[100000000000-199999999999]");
+    }
+
+    #[test]
+    fn test_malformed_ranges_are_rejected() {
+        for malformed in ["[1-]", "[-1]", "[2-1]", "[1-2", "[a-b]", "[1_2]", "[+1-2]"] {
+            assert!(RangedAccountId::from_str(malformed).is_err(), "{malformed}");
+        }
+    }
+}
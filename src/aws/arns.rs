@@ -0,0 +1,74 @@
+use crate::ResourceAbstract;
+use super::{AwsEngine, AwsPartition, AwsRegion, WildString};
+
+/// Shorthands for the ARN shapes of common AWS services.
+///
+/// Each helper fills in the `aws` partition and the service, and leaves out the segments the
+/// service does not use: S3 ARNs have neither region nor account, and IAM ARNs have no region.
+/// Arguments are used verbatim, so a `*` in them acts as a wildcard.
+impl ResourceAbstract<AwsEngine> {
+    fn aws(service: &str, region: Option<AwsRegion>, account: Option<&str>, resource_type: String) -> Self {
+        ResourceAbstract {
+            partition: Some(AwsPartition::Aws),
+            service: Some(WildString(service.to_string())),
+            region: region.map(Into::into),
+            account_id: account.map(|account| WildString(account.to_string())),
+            resource_type: Some(WildString(resource_type)),
+            resource_id: None,
+            qualifier: None,
+            trailing: Vec::new(),
+        }
+    }
+
+    /// Builds `arn:aws:s3:::<bucket>`.
+    pub fn s3_bucket(bucket: &str) -> Self {
+        Self::aws("s3", None, None, bucket.to_string())
+    }
+
+    /// Builds `arn:aws:s3:::<bucket>/<key>`.
+    pub fn s3_object(bucket: &str, key: &str) -> Self {
+        Self::aws("s3", None, None, format!("{}/{}", bucket, key))
+    }
+
+    /// Builds `arn:aws:iam::<account>:role/<name>`. `name` may include a path, e.g. `service/app`.
+    pub fn iam_role(account: &str, name: &str) -> Self {
+        Self::aws("iam", None, Some(account), format!("role/{}", name))
+    }
+
+    /// Builds `arn:aws:lambda:<region>:<account>:function:<name>`.
+    ///
+    /// # Errors
+    /// Returns an error if `name` is not a valid resource id.
+    pub fn lambda_function(region: AwsRegion, account: &str, name: &str) -> Result<Self, &'static str> {
+        let mut resource = Self::aws("lambda", Some(region), Some(account), "function".to_string());
+        resource.resource_id = Some(name.parse()?);
+        Ok(resource)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MatchesTrait;
+
+    type Resource = ResourceAbstract<AwsEngine>;
+
+    #[test]
+    fn test_service_helpers_render_expected_arns() {
+        assert_eq!(Resource::s3_bucket("reports").to_string(), "arn:aws:s3:::reports");
+        assert_eq!(Resource::s3_object("reports", "2024/q1.csv").to_string(), "arn:aws:s3:::reports/2024/q1.csv");
+        assert_eq!(Resource::iam_role("123456789012", "deployer").to_string(), "arn:aws:iam::123456789012:role/deployer");
+        assert_eq!(
+            Resource::lambda_function(AwsRegion::UsEastNVirginia, "123456789012", "my-function").unwrap().to_string(),
+            "arn:aws:lambda:us-east-1:123456789012:function:my-function"
+        );
+    }
+
+    #[test]
+    fn test_service_helpers_compose_patterns() {
+        let object = Resource::s3_object("reports", "2024/q1.csv");
+        assert_eq!(Resource::s3_object("reports", "*").matches(&object), Ok(true));
+        assert_eq!(Resource::s3_object("archive", "*").matches(&object), Ok(false));
+        assert_eq!(Resource::s3_bucket("reports").matches(&object), Ok(false));
+    }
+}
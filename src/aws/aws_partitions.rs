@@ -1,3 +1,4 @@
+use std::fmt;
 use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use crate::traits::MatchesTrait;
@@ -16,21 +17,38 @@ pub enum AwsPartition {
 impl FromStr for AwsPartition {
     type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Every partition name contains `aws`, so the China and GovCloud checks must run first
         match s.trim().to_lowercase().as_str() {
-            x if x.contains("ch") => Ok(AwsPartition::AwsChina),
-            x if x.contains("us") => Ok(AwsPartition::AwsUsGov),
-            x if x.contains("aws") => Ok(AwsPartition::AwsChina),
+            x if x.contains("cn") || x.contains("china") => Ok(AwsPartition::AwsChina),
+            x if x.contains("gov") => Ok(AwsPartition::AwsUsGov),
+            x if x.contains("aws") => Ok(AwsPartition::Aws),
             _ => Err("no match"),
         }
     }
 }
 
-impl ToString for AwsPartition {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for AwsPartition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
             AwsPartition::Aws => "aws",
             AwsPartition::AwsChina => "aws-cn",
             AwsPartition::AwsUsGov => "aws-us-gov",
-        }.to_string()
+        })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_partition_is_reachable() {
+        assert_eq!(AwsPartition::from_str("aws"), Ok(AwsPartition::Aws));
+        assert_eq!(AwsPartition::from_str("AWS"), Ok(AwsPartition::Aws));
+        assert_eq!(AwsPartition::from_str("aws-cn"), Ok(AwsPartition::AwsChina));
+        assert_eq!(AwsPartition::from_str("aws-china"), Ok(AwsPartition::AwsChina));
+        assert_eq!(AwsPartition::from_str("aws-us-gov"), Ok(AwsPartition::AwsUsGov));
+        assert_eq!(AwsPartition::from_str(" AWS-US-GOV "), Ok(AwsPartition::AwsUsGov));
+        assert_eq!(AwsPartition::from_str("azure"), Err("no match"));
+    }
+}
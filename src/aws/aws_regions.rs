@@ -1,6 +1,9 @@
+use std::cmp::Ordering;
+use std::fmt;
 use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use crate::traits::MatchesTrait;
+use crate::error::IamError;
 use matches_macro::Matches;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Matches)]
@@ -100,12 +103,243 @@ pub enum AwsRegion {
 
     #[serde(rename = "us-gov-west-1", alias = "aws govcloud us west", alias = "aws govcloud (us-west)")]
     AwsGovCloudUsWest,
+
+    /// A syntactically valid region code this crate does not know yet, e.g. a newly launched
+    /// region. The code is kept verbatim so it round-trips and matches only itself.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl AwsRegion {
+    /// Every region known to this crate, in declaration order.
+    pub const ALL: &'static [AwsRegion] = &[
+        AwsRegion::UsEastOhio,
+        AwsRegion::UsEastNVirginia,
+        AwsRegion::UsWestNCalifornia,
+        AwsRegion::UsWestOregon,
+        AwsRegion::AfricaCapeTown,
+        AwsRegion::AsiaPacificHongKong,
+        AwsRegion::AsiaPacificHyderabad,
+        AwsRegion::AsiaPacificJakarta,
+        AwsRegion::AsiaPacificMalaysia,
+        AwsRegion::AsiaPacificMelbourne,
+        AwsRegion::AsiaPacificMumbai,
+        AwsRegion::AsiaPacificOsaka,
+        AwsRegion::AsiaPacificSeoul,
+        AwsRegion::AsiaPacificSingapore,
+        AwsRegion::AsiaPacificSydney,
+        AwsRegion::AsiaPacificTokyo,
+        AwsRegion::CanadaCentral,
+        AwsRegion::CanadaWestCalgary,
+        AwsRegion::EuropeFrankfurt,
+        AwsRegion::EuropeIreland,
+        AwsRegion::EuropeLondon,
+        AwsRegion::EuropeMilan,
+        AwsRegion::EuropeParis,
+        AwsRegion::EuropeSpain,
+        AwsRegion::EuropeStockholm,
+        AwsRegion::EuropeZurich,
+        AwsRegion::IsraelTelAviv,
+        AwsRegion::MiddleEastBahrain,
+        AwsRegion::MiddleEastUAE,
+        AwsRegion::SouthAmericaSaoPaulo,
+        AwsRegion::AwsGovCloudUsEast,
+        AwsRegion::AwsGovCloudUsWest,
+    ];
+
+    /// Returns the human-readable names accepted for this region besides its code.
+    ///
+    /// These are the console names (with and without parentheses) plus the bare location
+    /// name where it is unambiguous, all in lowercase.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            AwsRegion::UsEastOhio => &["us east ohio", "us east (ohio)", "ohio"],
+            AwsRegion::UsEastNVirginia => &["us east n virginia", "us east (n. virginia)", "n. virginia"],
+            AwsRegion::UsWestNCalifornia => &["us west n california", "us west (n. california)", "n. california"],
+            AwsRegion::UsWestOregon => &["us west oregon", "us west (oregon)", "oregon"],
+            AwsRegion::AfricaCapeTown => &["africa cape town", "africa (cape town)", "cape town"],
+            AwsRegion::AsiaPacificHongKong => &["asia pacific hong kong", "asia pacific (hong kong)", "hong kong"],
+            AwsRegion::AsiaPacificHyderabad => &["asia pacific hyderabad", "asia pacific (hyderabad)", "hyderabad"],
+            AwsRegion::AsiaPacificJakarta => &["asia pacific jakarta", "asia pacific (jakarta)", "jakarta"],
+            AwsRegion::AsiaPacificMalaysia => &["asia pacific malaysia", "asia pacific (malaysia)", "malaysia"],
+            AwsRegion::AsiaPacificMelbourne => &["asia pacific melbourne", "asia pacific (melbourne)", "melbourne"],
+            AwsRegion::AsiaPacificMumbai => &["asia pacific mumbai", "asia pacific (mumbai)", "mumbai"],
+            AwsRegion::AsiaPacificOsaka => &["asia pacific osaka", "asia pacific (osaka)", "osaka"],
+            AwsRegion::AsiaPacificSeoul => &["asia pacific seoul", "asia pacific (seoul)", "seoul"],
+            AwsRegion::AsiaPacificSingapore => &["asia pacific singapore", "asia pacific (singapore)", "singapore"],
+            AwsRegion::AsiaPacificSydney => &["asia pacific sydney", "asia pacific (sydney)", "sydney"],
+            AwsRegion::AsiaPacificTokyo => &["asia pacific tokyo", "asia pacific (tokyo)", "tokyo"],
+            AwsRegion::CanadaCentral => &["canada central", "canada (central)"],
+            AwsRegion::CanadaWestCalgary => &["canada west calgary", "canada west (calgary)", "calgary"],
+            AwsRegion::EuropeFrankfurt => &["europe frankfurt", "europe (frankfurt)", "frankfurt"],
+            AwsRegion::EuropeIreland => &["europe ireland", "europe (ireland)", "ireland"],
+            AwsRegion::EuropeLondon => &["europe london", "europe (london)", "london"],
+            AwsRegion::EuropeMilan => &["europe milan", "europe (milan)", "milan"],
+            AwsRegion::EuropeParis => &["europe paris", "europe (paris)", "paris"],
+            AwsRegion::EuropeSpain => &["europe spain", "europe (spain)", "spain"],
+            AwsRegion::EuropeStockholm => &["europe stockholm", "europe (stockholm)", "stockholm"],
+            AwsRegion::EuropeZurich => &["europe zurich", "europe (zurich)", "zurich"],
+            AwsRegion::IsraelTelAviv => &["israel tel aviv", "israel (tel aviv)", "tel aviv"],
+            AwsRegion::MiddleEastBahrain => &["middle east bahrain", "middle east (bahrain)", "bahrain"],
+            AwsRegion::MiddleEastUAE => &["middle east uae", "middle east (uae)", "uae"],
+            AwsRegion::SouthAmericaSaoPaulo => &["south america sao paulo", "south america (são paulo)", "são paulo", "sao paulo"],
+            AwsRegion::AwsGovCloudUsEast => &["aws govcloud us east", "aws govcloud (us-east)"],
+            AwsRegion::AwsGovCloudUsWest => &["aws govcloud us west", "aws govcloud (us-west)"],
+            AwsRegion::Other(_) => &[],
+        }
+    }
+
+    /// Parses a region, accepting only canonical codes and known aliases.
+    ///
+    /// Unlike `from_str`, which falls back to fuzzy keyword matching and can resolve
+    /// unrelated input such as `"s-1"` to a real region, this only accepts an exact
+    /// region code (e.g. `"ap-southeast-1"`) or one of the names returned by
+    /// [`AwsRegion::aliases`] (e.g. `"singapore"`). Matching ignores case and surrounding
+    /// whitespace. Use it when the region comes from untrusted input.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::aws::AwsRegion;
+    ///
+    /// assert_eq!(AwsRegion::from_str_strict("Singapore"), Ok(AwsRegion::AsiaPacificSingapore));
+    /// assert!(AwsRegion::from_str_strict("s-1").is_err());
+    /// ```
+    pub fn from_str_strict(s: &str) -> Result<Self, &'static str> {
+        let input = s.trim().to_lowercase();
+        AwsRegion::ALL
+            .iter()
+            .find(|region| region.to_string() == input || region.aliases().contains(&input.as_str()))
+            .cloned()
+            .ok_or("Invalid Region")
+    }
+
+    /// Parses the region an availability zone belongs to.
+    ///
+    /// Standard zones are the region code followed by a letter (`us-east-1a`). Local and
+    /// Wavelength zones append further dash-separated parts after the region code
+    /// (`us-east-1-bos-1a`); the base region is returned for those. Unknown but well-formed
+    /// region codes yield [`AwsRegion::Other`].
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::aws::AwsRegion;
+    ///
+    /// assert_eq!(AwsRegion::from_availability_zone("eu-west-1b"), Ok(AwsRegion::EuropeIreland));
+    /// assert_eq!(AwsRegion::from_availability_zone("us-east-1-bos-1a"), Ok(AwsRegion::UsEastNVirginia));
+    /// ```
+    pub fn from_availability_zone(az: &str) -> Result<Self, &'static str> {
+        const INVALID: &str = "Invalid Availability Zone";
+        let parts: Vec<&str> = az.trim().split('-').collect();
+        let index = parts
+            .iter()
+            .position(|part| part.starts_with(|c: char| c.is_ascii_digit()))
+            .ok_or(INVALID)?;
+        let digits = parts[index].bytes().take_while(u8::is_ascii_digit).count();
+        let (number, zone) = parts[index].split_at(digits);
+        let rest = &parts[index + 1..];
+        let is_zone_letter = zone.len() == 1 && zone.bytes().all(|b| b.is_ascii_lowercase());
+        let is_local_zone = zone.is_empty()
+            && !rest.is_empty()
+            && rest.iter().all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit()));
+        if !(is_zone_letter && rest.is_empty() || is_local_zone) {
+            return Err(INVALID);
+        }
+        let code = format!("{}-{}", parts[..index].join("-"), number);
+        if !AwsRegion::is_region_code(&code) {
+            return Err(INVALID);
+        }
+        AwsRegion::from_str(&code).map_err(|_| INVALID)
+    }
+
+    /// Parses the region from a regional AWS endpoint hostname.
+    ///
+    /// The region is the label directly before `amazonaws.com` (or `amazonaws.com.cn` in the
+    /// China partition), so `s3.us-west-2.amazonaws.com`, `bucket.s3.us-west-2.amazonaws.com`
+    /// and `ec2-fips.us-gov-west-1.amazonaws.com` all work. Global endpoints such as
+    /// `iam.amazonaws.com` have no region and are rejected. Unknown but well-formed region
+    /// codes yield [`AwsRegion::Other`].
+    ///
+    /// # Errors
+    /// Returns `IamError::InvalidEndpoint` with the given hostname if it is not an AWS
+    /// endpoint or carries no region.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::IamError;
+    /// use rust_iam::aws::AwsRegion;
+    ///
+    /// assert_eq!(AwsRegion::from_endpoint("ec2.eu-central-1.amazonaws.com"), Ok(AwsRegion::EuropeFrankfurt));
+    /// assert_eq!(
+    ///     AwsRegion::from_endpoint("iam.amazonaws.com"),
+    ///     Err(IamError::InvalidEndpoint("iam.amazonaws.com".to_string()))
+    /// );
+    /// ```
+    pub fn from_endpoint(host: &str) -> Result<Self, IamError> {
+        let invalid = || IamError::InvalidEndpoint(host.to_string());
+        let normalized = host.trim().trim_end_matches('.').to_lowercase();
+        let prefix = normalized
+            .strip_suffix(".amazonaws.com")
+            .or_else(|| normalized.strip_suffix(".amazonaws.com.cn"))
+            .ok_or_else(invalid)?;
+        let code = prefix.rsplit('.').next().unwrap_or_default();
+        if !AwsRegion::is_region_code(code) {
+            return Err(invalid());
+        }
+        AwsRegion::from_str(code).map_err(|_| invalid())
+    }
+
+    /// Returns every known region sorted by geography, then by code (see the `Ord` impl).
+    pub fn sorted_all() -> Vec<AwsRegion> {
+        let mut regions = AwsRegion::ALL.to_vec();
+        regions.sort();
+        regions
+    }
+
+    /// Ranks the region's geography by its code prefix, for ordering.
+    ///
+    /// North America, South America, Europe, Middle East, Africa, Asia Pacific, then unknown areas.
+    fn geography_rank(&self) -> u8 {
+        let code = self.to_string();
+        match code.split('-').next().unwrap_or_default() {
+            "us" | "ca" | "mx" => 0,
+            "sa" => 1,
+            "eu" => 2,
+            "me" | "il" => 3,
+            "af" => 4,
+            "ap" => 5,
+            _ => 6,
+        }
+    }
+
+    /// Returns `true` if `s` has the shape of a region code: a two-letter area, one or more
+    /// lowercase words, and a number, separated by dashes (e.g. `us-gov-west-1`).
+    fn is_region_code(s: &str) -> bool {
+        let parts: Vec<&str> = s.split('-').collect();
+        match parts.as_slice() {
+            [area, words @ .., number] if !words.is_empty() => {
+                area.len() == 2
+                    && area.bytes().all(|b| b.is_ascii_lowercase())
+                    && words.iter().all(|w| !w.is_empty() && w.bytes().all(|b| b.is_ascii_lowercase()))
+                    && !number.is_empty()
+                    && number.bytes().all(|b| b.is_ascii_digit())
+            }
+            _ => false,
+        }
+    }
 }
 
 impl FromStr for AwsRegion {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Exact codes and aliases first, so fuzzy keywords cannot shadow a canonical code
+        if let Ok(region) = AwsRegion::from_str_strict(s) {
+            return Ok(region);
+        }
+        // Then unknown but well-formed codes, so new regions are not fuzzily mapped to old ones
+        if AwsRegion::is_region_code(s.trim()) {
+            return Ok(AwsRegion::Other(s.trim().to_string()));
+        }
         match s.trim().to_lowercase().as_str() {
             // US Regions
             x if x.contains("east-2") || (x.contains("ohi")) => Ok(AwsRegion::UsEastOhio),
@@ -163,9 +397,26 @@ impl FromStr for AwsRegion {
     }
 }
 
-impl ToString for AwsRegion {
-    fn to_string(&self) -> String {
-        match self {
+/// Orders regions by geography (see [`AwsRegion::sorted_all`]) and then alphabetically by code,
+/// so that regions on the same continent are listed together regardless of declaration order.
+impl Ord for AwsRegion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.geography_rank()
+            .cmp(&other.geography_rank())
+            .then_with(|| self.to_string().cmp(&other.to_string()))
+            .then_with(|| matches!(self, AwsRegion::Other(_)).cmp(&matches!(other, AwsRegion::Other(_))))
+    }
+}
+
+impl PartialOrd for AwsRegion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for AwsRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
             AwsRegion::UsEastOhio => "us-east-2",
             AwsRegion::UsEastNVirginia => "us-east-1",
             AwsRegion::UsWestNCalifornia => "us-west-1",
@@ -198,8 +449,8 @@ impl ToString for AwsRegion {
             AwsRegion::SouthAmericaSaoPaulo => "sa-east-1",
             AwsRegion::AwsGovCloudUsEast => "us-gov-east-1",
             AwsRegion::AwsGovCloudUsWest => "us-gov-west-1",
-        }
-            .to_string()
+            AwsRegion::Other(code) => code,
+        })
     }
 }
 
@@ -214,7 +465,8 @@ mod tests {
         assert_eq!(AwsRegion::from_str("us-east-1"), Ok(AwsRegion::UsEastNVirginia));
         assert_eq!(AwsRegion::from_str("ap-south-1"), Ok(AwsRegion::AsiaPacificMumbai));
         assert_eq!(AwsRegion::from_str("eu-central-1"), Ok(AwsRegion::EuropeFrankfurt));
-        // assert_eq!(AwsRegion::from_str("us-gov-west-1"), Ok(AwsRegion::AwsGovCloudUsWest));
+        assert_eq!(AwsRegion::from_str("us-gov-west-1"), Ok(AwsRegion::AwsGovCloudUsWest));
+        assert_eq!(AwsRegion::from_str("ap-east-1"), Ok(AwsRegion::AsiaPacificHongKong));
     }
 
     #[test]
@@ -224,7 +476,7 @@ mod tests {
         assert_eq!(AwsRegion::from_str("us-east-ohio"), Ok(AwsRegion::UsEastOhio));
         assert_eq!(AwsRegion::from_str("us-east-n.virginia"), Ok(AwsRegion::UsEastNVirginia));
         assert_eq!(AwsRegion::from_str("asia pacific mumbai"), Ok(AwsRegion::AsiaPacificMumbai));
-        // assert_eq!(AwsRegion::from_str("europe frankfurt"), Ok(AwsRegion::EuropeFrankfurt));
+        assert_eq!(AwsRegion::from_str("europe frankfurt"), Ok(AwsRegion::EuropeFrankfurt));
     }
 
     #[test]
@@ -257,4 +509,87 @@ mod tests {
         assert_eq!(AwsRegion::from_str("   "), Err("Invalid Region")); // Whitespace only
         assert_eq!(AwsRegion::from_str("US-EAST-2\n"), Ok(AwsRegion::UsEastOhio)); // Trailing newline
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_strict_accepts_codes_and_aliases() {
+        assert_eq!(AwsRegion::from_str_strict("ap-southeast-1"), Ok(AwsRegion::AsiaPacificSingapore));
+        assert_eq!(AwsRegion::from_str_strict("singapore"), Ok(AwsRegion::AsiaPacificSingapore));
+        assert_eq!(AwsRegion::from_str_strict(" Asia Pacific (Singapore) "), Ok(AwsRegion::AsiaPacificSingapore));
+        for region in AwsRegion::ALL {
+            assert_eq!(AwsRegion::from_str_strict(&region.to_string()).as_ref(), Ok(region));
+        }
+    }
+
+    #[test]
+    fn test_strict_rejects_fuzzy_input() {
+        assert_eq!(AwsRegion::from_str_strict("s-1"), Err("Invalid Region"));
+        assert_eq!(AwsRegion::from_str_strict("random"), Err("Invalid Region"));
+        assert_eq!(AwsRegion::from_str_strict("ohi"), Err("Invalid Region"));
+        assert_eq!(AwsRegion::from_str_strict(""), Err("Invalid Region"));
+    }
+
+    #[test]
+    fn test_unknown_region_codes() {
+        assert_eq!(AwsRegion::from_str("eu-west-1"), Ok(AwsRegion::EuropeIreland));
+
+        let unknown = AwsRegion::from_str("xx-test-9").unwrap();
+        assert_eq!(unknown, AwsRegion::Other("xx-test-9".to_string()));
+        assert_eq!(unknown.to_string(), "xx-test-9");
+        assert_eq!(unknown.matches(&AwsRegion::from_str("xx-test-9").unwrap()), Ok(true));
+        assert_eq!(unknown.matches(&AwsRegion::from_str("xx-test-8").unwrap()), Ok(false));
+        assert_eq!(serde_json::to_string(&unknown).unwrap(), "\"xx-test-9\"");
+        assert_eq!(serde_json::from_str::<AwsRegion>("\"xx-test-9\"").unwrap(), unknown);
+        assert_eq!(serde_json::from_str::<AwsRegion>("\"us-east-1\"").unwrap(), AwsRegion::UsEastNVirginia);
+        assert_eq!(AwsRegion::from_str_strict("xx-test-9"), Err("Invalid Region"));
+
+        for malformed in ["xx-9", "x-test-9", "xx-test-", "xx--test-9", "xx-test-9a", "xx_test_9"] {
+            assert_eq!(AwsRegion::from_str(malformed), Err("Invalid Region"), "{malformed}");
+        }
+    }
+
+    #[test]
+    fn test_from_availability_zone() {
+        assert_eq!(AwsRegion::from_availability_zone("us-east-1a"), Ok(AwsRegion::UsEastNVirginia));
+        assert_eq!(AwsRegion::from_availability_zone("ap-southeast-2c"), Ok(AwsRegion::AsiaPacificSydney));
+        assert_eq!(AwsRegion::from_availability_zone("us-gov-west-1b"), Ok(AwsRegion::AwsGovCloudUsWest));
+        assert_eq!(AwsRegion::from_availability_zone("us-east-1-bos-1a"), Ok(AwsRegion::UsEastNVirginia));
+        assert_eq!(AwsRegion::from_availability_zone("us-west-2-lax-1b"), Ok(AwsRegion::UsWestOregon));
+        assert_eq!(AwsRegion::from_availability_zone("xx-test-9a"), Ok(AwsRegion::Other("xx-test-9".to_string())));
+
+        for invalid in ["us-east-1", "us-east-1ab", "us-east-1A", "use1-az1", "us-east-1-", "eu-west", "", "1a"] {
+            assert_eq!(AwsRegion::from_availability_zone(invalid), Err("Invalid Availability Zone"), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_from_endpoint() {
+        assert_eq!(AwsRegion::from_endpoint("s3.us-west-2.amazonaws.com"), Ok(AwsRegion::UsWestOregon));
+        assert_eq!(AwsRegion::from_endpoint("ec2.eu-central-1.amazonaws.com"), Ok(AwsRegion::EuropeFrankfurt));
+        assert_eq!(AwsRegion::from_endpoint("my-bucket.s3.ap-southeast-2.amazonaws.com."), Ok(AwsRegion::AsiaPacificSydney));
+        assert_eq!(AwsRegion::from_endpoint("ec2-fips.us-gov-west-1.amazonaws.com"), Ok(AwsRegion::AwsGovCloudUsWest));
+        assert_eq!(AwsRegion::from_endpoint("sqs.us-gov-east-1.amazonaws.com"), Ok(AwsRegion::AwsGovCloudUsEast));
+        assert_eq!(AwsRegion::from_endpoint("ec2.cn-north-1.amazonaws.com.cn"), Ok(AwsRegion::Other("cn-north-1".to_string())));
+        assert_eq!(AwsRegion::from_endpoint("S3.CN-NORTHWEST-1.AMAZONAWS.COM.CN"), Ok(AwsRegion::Other("cn-northwest-1".to_string())));
+
+        for invalid in ["iam.amazonaws.com", "s3.amazonaws.com", "amazonaws.com", "s3.us-west-2.example.com", "us-west-2", ""] {
+            assert_eq!(AwsRegion::from_endpoint(invalid), Err(IamError::InvalidEndpoint(invalid.to_string())), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_sorted_all_groups_by_geography() {
+        let sorted = AwsRegion::sorted_all();
+        assert_eq!(sorted.len(), AwsRegion::ALL.len());
+        assert_eq!(sorted[0], AwsRegion::CanadaCentral);
+
+        let codes: Vec<String> = sorted.iter().map(ToString::to_string).collect();
+        let eu: Vec<usize> = (0..codes.len()).filter(|&i| codes[i].starts_with("eu-")).collect();
+        assert_eq!(eu.len(), 8);
+        assert_eq!(eu.last().unwrap() - eu[0] + 1, eu.len(), "EU regions are not contiguous: {codes:?}");
+        let eu_codes: Vec<&String> = eu.iter().map(|&i| &codes[i]).collect();
+        assert!(eu_codes.windows(2).all(|w| w[0] < w[1]), "{eu_codes:?}");
+
+        assert!(AwsRegion::UsWestOregon < AwsRegion::SouthAmericaSaoPaulo);
+        assert!(AwsRegion::AsiaPacificTokyo < AwsRegion::Other("zz-new-1".to_string()));
+    }
+}
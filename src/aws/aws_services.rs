@@ -0,0 +1,528 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use crate::{EngineTrait, IamError, Statement};
+use crate::traits::MatchesTrait;
+use super::WildString;
+
+/// Declares the known services together with their ARN namespaces, keeping the enum, the
+/// `ALL` list and the `Display` table in one place.
+macro_rules! aws_services {
+    ($($variant:ident => $code:literal,)*) => {
+        /// An AWS service namespace, as it appears in ARNs and action prefixes.
+        ///
+        /// Unlike [`WildString`], parsing only accepts namespaces in [`AwsService::ALL`], so a
+        /// typo such as `s2` or `S3` is rejected. Use [`AwsService::from_str_lenient`] to keep
+        /// a well-formed namespace this crate does not list yet as [`AwsService::Other`]
+        /// instead. Input containing `*` or `?` (e.g. `*` or `s3*`) parses to
+        /// [`AwsService::Pattern`], which is matched as a wildcard against the namespace of the
+        /// other side.
+        ///
+        /// # Examples
+        /// ```
+        /// use rust_iam::aws::AwsService;
+        /// use rust_iam::traits::MatchesTrait;
+        ///
+        /// assert_eq!("s3".parse(), Ok(AwsService::S3));
+        /// assert!("s2".parse::<AwsService>().is_err());
+        /// assert_eq!(AwsService::from_str_lenient("s2"), Ok(AwsService::Other("s2".to_string())));
+        /// let pattern: AwsService = "s3*".parse().unwrap();
+        /// assert_eq!(pattern.matches(&AwsService::S3), Ok(true));
+        /// ```
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum AwsService {
+            $($variant,)*
+            /// A wildcard pattern over service namespaces.
+            Pattern(String),
+            /// A well-formed namespace this crate does not know yet, e.g. a newly launched
+            /// service. The namespace is kept verbatim so it round-trips and matches only itself.
+            Other(String),
+        }
+
+        impl AwsService {
+            /// Every service known to this crate.
+            pub const ALL: &'static [AwsService] = &[$(AwsService::$variant,)*];
+
+            /// Returns the service namespace, or the pattern text for [`AwsService::Pattern`].
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(AwsService::$variant => $code,)*
+                    AwsService::Pattern(pattern) => pattern,
+                    AwsService::Other(namespace) => namespace,
+                }
+            }
+        }
+    };
+}
+
+aws_services! {
+    AccessAnalyzer => "access-analyzer",
+    Account => "account",
+    Acm => "acm",
+    AcmPca => "acm-pca",
+    Airflow => "airflow",
+    Amplify => "amplify",
+    AmplifyBackend => "amplifybackend",
+    AmplifyUiBuilder => "amplifyuibuilder",
+    OpenSearchServerless => "aoss",
+    ApiGateway => "apigateway",
+    AppIntegrations => "app-integrations",
+    AppConfig => "appconfig",
+    AppFabric => "appfabric",
+    AppFlow => "appflow",
+    ApplicationAutoScaling => "application-autoscaling",
+    ApplicationInsights => "applicationinsights",
+    AppMesh => "appmesh",
+    AppRunner => "apprunner",
+    AppStream => "appstream",
+    AppSync => "appsync",
+    Prometheus => "aps",
+    ArcZonalShift => "arc-zonal-shift",
+    Artifact => "artifact",
+    Athena => "athena",
+    AuditManager => "auditmanager",
+    AutoScaling => "autoscaling",
+    AutoScalingPlans => "autoscaling-plans",
+    Marketplace => "aws-marketplace",
+    BillingPortal => "aws-portal",
+    Backup => "backup",
+    BackupGateway => "backup-gateway",
+    BackupStorage => "backup-storage",
+    Batch => "batch",
+    BcmDataExports => "bcm-data-exports",
+    Bedrock => "bedrock",
+    Billing => "billing",
+    BillingConductor => "billingconductor",
+    Braket => "braket",
+    Budgets => "budgets",
+    Keyspaces => "cassandra",
+    CostExplorer => "ce",
+    Chatbot => "chatbot",
+    Chime => "chime",
+    CleanRooms => "cleanrooms",
+    Cloud9 => "cloud9",
+    CloudDirectory => "clouddirectory",
+    CloudFormation => "cloudformation",
+    CloudFront => "cloudfront",
+    CloudHsm => "cloudhsm",
+    CloudSearch => "cloudsearch",
+    CloudShell => "cloudshell",
+    CloudTrail => "cloudtrail",
+    CloudWatch => "cloudwatch",
+    CodeArtifact => "codeartifact",
+    CodeBuild => "codebuild",
+    CodeCatalyst => "codecatalyst",
+    CodeCommit => "codecommit",
+    CodeConnections => "codeconnections",
+    CodeDeploy => "codedeploy",
+    CodeGuruProfiler => "codeguru-profiler",
+    CodeGuruReviewer => "codeguru-reviewer",
+    CodePipeline => "codepipeline",
+    CodeStar => "codestar",
+    CodeStarConnections => "codestar-connections",
+    CodeStarNotifications => "codestar-notifications",
+    CodeWhisperer => "codewhisperer",
+    CognitoIdentity => "cognito-identity",
+    CognitoIdp => "cognito-idp",
+    CognitoSync => "cognito-sync",
+    Comprehend => "comprehend",
+    ComprehendMedical => "comprehendmedical",
+    ComputeOptimizer => "compute-optimizer",
+    Config => "config",
+    Connect => "connect",
+    ControlTower => "controltower",
+    Cur => "cur",
+    DataBrew => "databrew",
+    DataExchange => "dataexchange",
+    DataPipeline => "datapipeline",
+    DataSync => "datasync",
+    DataZone => "datazone",
+    Dax => "dax",
+    DeepRacer => "deepracer",
+    Detective => "detective",
+    DeviceFarm => "devicefarm",
+    DevOpsGuru => "devops-guru",
+    DirectConnect => "directconnect",
+    Discovery => "discovery",
+    Dlm => "dlm",
+    Dms => "dms",
+    DocDbElastic => "docdb-elastic",
+    Drs => "drs",
+    DirectoryService => "ds",
+    DynamoDb => "dynamodb",
+    Ebs => "ebs",
+    Ec2 => "ec2",
+    Ec2InstanceConnect => "ec2-instance-connect",
+    Ec2Messages => "ec2messages",
+    Ecr => "ecr",
+    EcrPublic => "ecr-public",
+    Ecs => "ecs",
+    Eks => "eks",
+    ElasticInference => "elastic-inference",
+    ElastiCache => "elasticache",
+    ElasticBeanstalk => "elasticbeanstalk",
+    ElasticFileSystem => "elasticfilesystem",
+    ElasticLoadBalancing => "elasticloadbalancing",
+    ElasticMapReduce => "elasticmapreduce",
+    ElasticTranscoder => "elastictranscoder",
+    EmrContainers => "emr-containers",
+    EmrServerless => "emr-serverless",
+    EntityResolution => "entityresolution",
+    OpenSearch => "es",
+    Events => "events",
+    Evidently => "evidently",
+    ExecuteApi => "execute-api",
+    FinSpace => "finspace",
+    Firehose => "firehose",
+    Fis => "fis",
+    FirewallManager => "fms",
+    Forecast => "forecast",
+    FraudDetector => "frauddetector",
+    FreeTier => "freetier",
+    Fsx => "fsx",
+    GameLift => "gamelift",
+    Geo => "geo",
+    Glacier => "glacier",
+    GlobalAccelerator => "globalaccelerator",
+    Glue => "glue",
+    Grafana => "grafana",
+    Greengrass => "greengrass",
+    GroundStation => "groundstation",
+    GuardDuty => "guardduty",
+    Health => "health",
+    HealthLake => "healthlake",
+    Iam => "iam",
+    IdentityStore => "identitystore",
+    ImageBuilder => "imagebuilder",
+    Inspector => "inspector",
+    Inspector2 => "inspector2",
+    InternetMonitor => "internetmonitor",
+    Iot => "iot",
+    IotAnalytics => "iotanalytics",
+    IotEvents => "iotevents",
+    IotFleetWise => "iotfleetwise",
+    IotSiteWise => "iotsitewise",
+    IotTwinMaker => "iottwinmaker",
+    IotWireless => "iotwireless",
+    Ivs => "ivs",
+    IvsChat => "ivschat",
+    Kafka => "kafka",
+    KafkaCluster => "kafka-cluster",
+    KafkaConnect => "kafkaconnect",
+    Kendra => "kendra",
+    Kinesis => "kinesis",
+    KinesisAnalytics => "kinesisanalytics",
+    KinesisVideo => "kinesisvideo",
+    Kms => "kms",
+    LakeFormation => "lakeformation",
+    Lambda => "lambda",
+    LaunchWizard => "launchwizard",
+    Lex => "lex",
+    LicenseManager => "license-manager",
+    Lightsail => "lightsail",
+    Logs => "logs",
+    LookoutEquipment => "lookoutequipment",
+    LookoutMetrics => "lookoutmetrics",
+    LookoutVision => "lookoutvision",
+    MainframeModernization => "m2",
+    Macie2 => "macie2",
+    ManagedBlockchain => "managedblockchain",
+    MediaConnect => "mediaconnect",
+    MediaConvert => "mediaconvert",
+    MediaLive => "medialive",
+    MediaPackage => "mediapackage",
+    MediaPackageV2 => "mediapackagev2",
+    MediaStore => "mediastore",
+    MediaTailor => "mediatailor",
+    MemoryDb => "memorydb",
+    MigrationHub => "mgh",
+    Mgn => "mgn",
+    MobileTargeting => "mobiletargeting",
+    Mq => "mq",
+    NeptuneDb => "neptune-db",
+    NeptuneGraph => "neptune-graph",
+    NetworkFirewall => "network-firewall",
+    NetworkManager => "networkmanager",
+    Oam => "oam",
+    Omics => "omics",
+    OpsWorks => "opsworks",
+    Organizations => "organizations",
+    Osis => "osis",
+    Outposts => "outposts",
+    Payments => "payments",
+    Personalize => "personalize",
+    PerformanceInsights => "pi",
+    Pipes => "pipes",
+    Polly => "polly",
+    Pricing => "pricing",
+    Proton => "proton",
+    Q => "q",
+    QBusiness => "qbusiness",
+    Qldb => "qldb",
+    QuickSight => "quicksight",
+    Ram => "ram",
+    RecycleBin => "rbin",
+    Rds => "rds",
+    RdsData => "rds-data",
+    RdsDb => "rds-db",
+    Redshift => "redshift",
+    RedshiftData => "redshift-data",
+    RedshiftServerless => "redshift-serverless",
+    Rekognition => "rekognition",
+    ResilienceHub => "resiliencehub",
+    ResourceExplorer2 => "resource-explorer-2",
+    ResourceGroups => "resource-groups",
+    RoboMaker => "robomaker",
+    RolesAnywhere => "rolesanywhere",
+    Route53 => "route53",
+    Route53RecoveryCluster => "route53-recovery-cluster",
+    Route53RecoveryControlConfig => "route53-recovery-control-config",
+    Route53RecoveryReadiness => "route53-recovery-readiness",
+    Route53Domains => "route53domains",
+    Route53Resolver => "route53resolver",
+    Rum => "rum",
+    S3 => "s3",
+    S3ObjectLambda => "s3-object-lambda",
+    S3Outposts => "s3-outposts",
+    S3Express => "s3express",
+    SageMaker => "sagemaker",
+    SavingsPlans => "savingsplans",
+    Scheduler => "scheduler",
+    Schemas => "schemas",
+    SimpleDb => "sdb",
+    SecretsManager => "secretsmanager",
+    SecurityHub => "securityhub",
+    SecurityLake => "securitylake",
+    ServerlessRepo => "serverlessrepo",
+    ServiceCatalog => "servicecatalog",
+    ServiceDiscovery => "servicediscovery",
+    ServiceQuotas => "servicequotas",
+    Ses => "ses",
+    Shield => "shield",
+    Signer => "signer",
+    SmsVoice => "sms-voice",
+    Snowball => "snowball",
+    Sns => "sns",
+    Sqs => "sqs",
+    Ssm => "ssm",
+    SsmContacts => "ssm-contacts",
+    SsmIncidents => "ssm-incidents",
+    SsmMessages => "ssmmessages",
+    Sso => "sso",
+    SsoDirectory => "sso-directory",
+    States => "states",
+    StorageGateway => "storagegateway",
+    Sts => "sts",
+    Support => "support",
+    Swf => "swf",
+    Synthetics => "synthetics",
+    Tag => "tag",
+    Textract => "textract",
+    Timestream => "timestream",
+    Transcribe => "transcribe",
+    Transfer => "transfer",
+    Translate => "translate",
+    TrustedAdvisor => "trustedadvisor",
+    VerifiedPermissions => "verifiedpermissions",
+    VoiceId => "voiceid",
+    VpcLattice => "vpc-lattice",
+    Waf => "waf",
+    WafRegional => "waf-regional",
+    WafV2 => "wafv2",
+    WellArchitected => "wellarchitected",
+    Wisdom => "wisdom",
+    WorkDocs => "workdocs",
+    WorkMail => "workmail",
+    WorkSpaces => "workspaces",
+    WorkSpacesWeb => "workspaces-web",
+    XRay => "xray",
+}
+
+impl AwsService {
+    /// Parses a service, keeping a well-formed namespace this crate does not list as
+    /// [`AwsService::Other`].
+    ///
+    /// Unlike `from_str`, an unknown namespace such as `ecs-tasks` is accepted, so services
+    /// launched after this release keep working. Text that cannot be a namespace, such as
+    /// `S3` or `ec2 `, is still an error.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::aws::AwsService;
+    ///
+    /// assert_eq!(AwsService::from_str_lenient("codebuild"), Ok(AwsService::CodeBuild));
+    /// assert_eq!(AwsService::from_str_lenient("ecs-tasks"), Ok(AwsService::Other("ecs-tasks".to_string())));
+    /// assert!(AwsService::from_str_lenient("S3").is_err());
+    /// ```
+    pub fn from_str_lenient(s: &str) -> Result<Self, &'static str> {
+        match AwsService::from_str(s) {
+            Err(_) if AwsService::is_namespace(s) => Ok(AwsService::Other(s.to_string())),
+            service => service,
+        }
+    }
+
+    /// Returns `true` if `s` is shaped like a service namespace: lowercase ASCII letters,
+    /// digits and `-`, starting with a letter.
+    fn is_namespace(s: &str) -> bool {
+        s.starts_with(|c: char| c.is_ascii_lowercase())
+            && s.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+    }
+}
+
+impl FromStr for AwsService {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains(['*', '?']) {
+            return Ok(AwsService::Pattern(s.to_string()));
+        }
+        AwsService::ALL
+            .iter()
+            .find(|service| service.as_str() == s)
+            .cloned()
+            .ok_or("Invalid Service")
+    }
+}
+
+impl fmt::Display for AwsService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl MatchesTrait<bool> for AwsService {
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        match self {
+            AwsService::Pattern(pattern) => {
+                WildString(pattern.clone()).matches(&WildString(value.as_str().to_string()))
+            }
+            _ => Ok(self == value),
+        }
+    }
+}
+
+impl Serialize for AwsService {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AwsService {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        AwsService::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl<Engine: EngineTrait<Service = AwsService>> Statement<Engine> {
+    /// Checks that every action's service prefix is a known [`AwsService`], catching typos
+    /// such as `s2:GetObject`.
+    ///
+    /// A prefix is known if it is in [`AwsService::ALL`], so a namespace this crate does not
+    /// list yet is reported too.
+    ///
+    /// The prefix is the text before the action's first `:` and is compared ignoring ASCII
+    /// case, as AWS does. Actions without a prefix (such as `*`) and prefixes containing a
+    /// wildcard (such as `*:Get*`) are exempt.
+    ///
+    /// # Errors
+    /// Returns `IamError::UnknownService` with the first action whose prefix is unknown.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::Statement;
+    /// use rust_iam::aws::AwsServiceEnumEngine;
+    ///
+    /// let statement: Statement<AwsServiceEnumEngine> = serde_json::from_str(
+    ///     r#"{"effect": "allow", "actions": ["s2:GetObject"], "resources": ["*"]}"#,
+    /// ).unwrap();
+    /// assert!(statement.validate_action_services().is_err());
+    /// ```
+    pub fn validate_action_services(&self) -> Result<(), IamError> {
+        for action in &self.actions {
+            let action = action.to_string();
+            let Some((service, _)) = action.split_once(':') else {
+                continue;
+            };
+            if !service.contains(['*', '?']) && AwsService::from_str(&service.to_ascii_lowercase()).is_err() {
+                return Err(IamError::UnknownService(action));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_services_round_trip() {
+        for service in AwsService::ALL {
+            assert_eq!(AwsService::from_str(&service.to_string()).as_ref(), Ok(service));
+        }
+        assert_eq!(serde_json::to_string(&AwsService::ExecuteApi).unwrap(), "\"execute-api\"");
+        assert_eq!(serde_json::from_str::<AwsService>("\"s3\"").unwrap(), AwsService::S3);
+        assert!(serde_json::from_str::<AwsService>("\"S3\"").is_err());
+    }
+
+    #[test]
+    fn test_unknown_namespaces_parse_as_other() {
+        for namespace in ["ses", "cognito-idp", "codebuild", "backup", "xray"] {
+            let service = AwsService::from_str(namespace).unwrap();
+            assert!(!matches!(service, AwsService::Other(_)), "{namespace}");
+            assert_eq!(AwsService::from_str_lenient(namespace), Ok(service));
+        }
+        assert!(AwsService::from_str("ecs-tasks").is_err());
+        let tasks = AwsService::from_str_lenient("ecs-tasks").unwrap();
+        assert_eq!(tasks, AwsService::Other("ecs-tasks".to_string()));
+        assert_eq!(tasks.to_string(), "ecs-tasks");
+        assert_eq!(tasks.matches(&AwsService::from_str_lenient("ecs-tasks").unwrap()), Ok(true));
+        assert_eq!(tasks.matches(&AwsService::Ecs), Ok(false));
+        assert_eq!(AwsService::from_str("ecs*").unwrap().matches(&tasks), Ok(true));
+        assert!(serde_json::from_str::<AwsService>("\"ecs-tasks\"").is_err());
+    }
+
+    #[test]
+    fn test_typos_are_rejected() {
+        for typo in ["s2", "ec-2", "lamda"] {
+            assert_eq!(AwsService::from_str(typo), Err("Invalid Service"), "{typo}");
+            assert_eq!(AwsService::from_str_lenient(typo), Ok(AwsService::Other(typo.to_string())), "{typo}");
+        }
+        for malformed in ["S3", "ec2 ", "-s3", "3s", ""] {
+            assert_eq!(AwsService::from_str(malformed), Err("Invalid Service"), "{malformed}");
+            assert_eq!(AwsService::from_str_lenient(malformed), Err("Invalid Service"), "{malformed}");
+        }
+    }
+
+    #[test]
+    fn test_validate_action_services() {
+        let statement = |actions: &str| -> Statement<crate::aws::AwsServiceEnumEngine> {
+            serde_json::from_str(&format!(r#"{{"effect": "allow", "actions": {actions}, "resources": ["*"]}}"#)).unwrap()
+        };
+        assert_eq!(statement(r#"["s3:GetObject", "execute-api:Invoke", "IAM:PassRole"]"#).validate_action_services(), Ok(()));
+        assert_eq!(statement(r#"["*", "*:Describe*", "s3*:Get*", "ec2:*"]"#).validate_action_services(), Ok(()));
+        assert_eq!(
+            statement(r#"["ses:SendEmail", "codebuild:StartBuild", "cognito-idp:AdminGetUser", "xray:PutTraceSegments"]"#)
+                .validate_action_services(),
+            Ok(())
+        );
+        assert_eq!(
+            statement(r#"["s3:GetObject", "s2:GetObject", "lamda:InvokeFunction"]"#).validate_action_services(),
+            Err(IamError::UnknownService("s2:GetObject".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_patterns_glob_over_namespaces() {
+        let s3 = AwsService::from_str("s3").unwrap();
+        let s3_glob = AwsService::from_str("s3*").unwrap();
+        assert_eq!(s3_glob, AwsService::Pattern("s3*".to_string()));
+        assert_eq!(s3.matches(&AwsService::S3), Ok(true));
+        assert_eq!(s3.matches(&AwsService::Sqs), Ok(false));
+        assert_eq!(s3_glob.matches(&AwsService::S3), Ok(true));
+        assert_eq!(s3_glob.matches(&AwsService::Sqs), Ok(false));
+        assert_eq!(AwsService::from_str("s*").unwrap().matches(&AwsService::Sqs), Ok(true));
+        assert_eq!(AwsService::from_str("*").unwrap().matches(&AwsService::Lambda), Ok(true));
+    }
+}
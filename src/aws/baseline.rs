@@ -0,0 +1,55 @@
+use crate::{Effect, EngineTrait, ResourceAbstract, Statement};
+use super::WildString;
+
+/// Read-only housekeeping actions that reveal nothing beyond the caller's own identity and
+/// the public layout of AWS, granted by [`Statement::allow_baseline_actions`].
+pub const BASELINE_ACTIONS: &[&str] = &[
+    "sts:GetCallerIdentity",
+    "iam:ListAccountAliases",
+    "ec2:DescribeRegions",
+    "ec2:DescribeAvailabilityZones",
+];
+
+impl<Engine: EngineTrait<Action = WildString>> Statement<Engine> {
+    /// Builds a statement allowing exactly the [`BASELINE_ACTIONS`] on every resource.
+    ///
+    /// Add it to a policy to opt into the curated baseline. The actions are listed literally,
+    /// so the statement never allows anything else.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::{MaybeEffect, ResourceAbstract, Statement};
+    /// use rust_iam::aws::{AwsEngine, WildString};
+    ///
+    /// let baseline = Statement::<AwsEngine>::allow_baseline_actions();
+    /// let action = WildString("sts:GetCallerIdentity".to_string());
+    /// assert_eq!(baseline.matches(&action, &ResourceAbstract::any()), MaybeEffect::Allow);
+    /// ```
+    pub fn allow_baseline_actions() -> Self {
+        Statement {
+            effect: Effect::Allow,
+            actions: BASELINE_ACTIONS.iter().map(|action| WildString(action.to_string())).collect(),
+            resources: vec![ResourceAbstract::any()],
+            except_resources: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::AwsEngine;
+    use crate::MaybeEffect;
+
+    #[test]
+    fn test_baseline_allows_listed_actions_only() {
+        let baseline = Statement::<AwsEngine>::allow_baseline_actions();
+        let resource: ResourceAbstract<AwsEngine> = "arn:aws:iam::123456789012:user/alice".parse().unwrap();
+        for action in BASELINE_ACTIONS {
+            assert_eq!(baseline.matches(&WildString(action.to_string()), &resource), MaybeEffect::Allow, "{action}");
+        }
+        for action in ["sts:AssumeRole", "sts:GetCallerIdentityX", "iam:ListUsers", "ec2:DescribeInstances", "s3:GetObject"] {
+            assert_eq!(baseline.matches(&WildString(action.to_string()), &resource), MaybeEffect::NotSpecified, "{action}");
+        }
+    }
+}
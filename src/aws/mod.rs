@@ -4,24 +4,50 @@ use serde::{Deserialize, Serialize};
 
 mod aws_partitions;
 mod aws_regions;
+mod aws_services;
+mod region_pattern;
+mod resource_id;
+mod account_id;
+mod arns;
+mod baseline;
 
 use crate::traits::MatchesTrait;
+#[cfg(not(feature = "match-cache"))]
 use matches_macro::Matches;
 use crate::engine::EngineTrait;
+use crate::error::IamError;
 
 pub use aws_regions::*;
+pub use aws_services::*;
+pub use region_pattern::*;
 pub use aws_partitions::*;
+pub use resource_id::*;
+pub use account_id::*;
+pub use baseline::BASELINE_ACTIONS;
 
 #[derive(Debug, Copy, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct AwsEngine{}
 
-#[derive(Debug, PartialEq, Eq, Matches, Serialize, Deserialize, Clone)]
-#[wildcard_matching]
+/// A string segment matched with `*` and `?` wildcards.
+///
+/// With the `match-cache` feature, match results are memoized per thread, keyed on both the
+/// pattern and the value.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(not(feature = "match-cache"), derive(Matches), wildcard_matching)]
 pub struct WildString(pub String);
 
+#[cfg(feature = "match-cache")]
+impl MatchesTrait<bool> for WildString {
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        crate::resource_ref::assert_pattern_value(&self.0, &value.0);
+        crate::match_cache::cached_glob(&self.0, &value.0)
+    }
+}
+
 
 #[cfg(feature = "with-sqlx")]
 use sqlx::{Decode, Encode, Type, Postgres};
+#[cfg(feature = "with-sqlx")]
 use serde::ser::StdError;
 
 
@@ -53,7 +79,7 @@ impl Encode<'_, Postgres> for WildString {
 
 impl Display for WildString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0.clone())
+        f.write_str(&self.0)
     }
 }
 
@@ -64,12 +90,239 @@ impl FromStr for WildString {
     }
 }
 
+impl WildString {
+    /// Builds a pattern after checking it against complexity limits.
+    ///
+    /// Patterns with several wildcards are compiled into an automaton whose matching cost grows
+    /// with both the pattern and the input, so services accepting user-supplied policies should
+    /// bound them before use. Escaped wildcards (`\*`, `\?`) are literal and are not counted.
+    ///
+    /// # Parameters
+    /// - `s`: The pattern text.
+    /// - `max_wildcards`: The largest number of unescaped `*` and `?` allowed.
+    /// - `max_len`: The largest pattern length allowed, in bytes.
+    ///
+    /// # Errors
+    /// Returns `IamError::PatternTooComplex` if the pattern is longer than `max_len` or has
+    /// more than `max_wildcards` wildcards.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::aws::WildString;
+    ///
+    /// assert!(WildString::validated_with_limits("s3:Get*", 4, 128).is_ok());
+    /// assert!(WildString::validated_with_limits("*a*a*a*a*a*", 4, 128).is_err());
+    /// ```
+    pub fn validated_with_limits(s: &str, max_wildcards: usize, max_len: usize) -> Result<Self, IamError> {
+        let too_complex = |reason| IamError::PatternTooComplex { pattern: s.to_string(), reason };
+        if s.len() > max_len {
+            return Err(too_complex("pattern is too long"));
+        }
+        let mut escaped = false;
+        let mut wildcards = 0;
+        for c in s.chars() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '*' | '?' => wildcards += 1,
+                _ => {}
+            }
+        }
+        if wildcards > max_wildcards {
+            return Err(too_complex("pattern has too many wildcards"));
+        }
+        Ok(WildString(s.to_string()))
+    }
+}
+
 impl EngineTrait for AwsEngine {
     type Action = WildString;
     type Partition = AwsPartition;
     type Service = WildString;
-    type Region = AwsRegion;
+    type Region = RegionPattern;
+    type AccountID = WildString;
+    type ResourceType = WildString;
+    type ResourceID = ResourceIdString;
+
+    fn default_partition() -> Option<AwsPartition> {
+        Some(AwsPartition::Aws)
+    }
+
+    fn name() -> &'static str {
+        "AWS"
+    }
+
+    fn resource_format_example() -> &'static str {
+        "arn:aws:ec2:us-east-1:123456789012:instance:i-0abcd1234efgh5678"
+    }
+}
+
+/// An [`AwsEngine`] variant whose service segment is the typed [`AwsService`] enum.
+///
+/// Known services are typed and `*` and patterns such as `s3*` still match by glob, while a
+/// service segment not in [`AwsService::ALL`], such as the typo `s2`, fails to parse. Every
+/// other segment behaves as in [`AwsEngine`].
+#[derive(Debug, Copy, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AwsServiceEnumEngine {}
+
+impl EngineTrait for AwsServiceEnumEngine {
+    type Action = WildString;
+    type Partition = AwsPartition;
+    type Service = AwsService;
+    type Region = RegionPattern;
+    type AccountID = WildString;
+    type ResourceType = WildString;
+    type ResourceID = ResourceIdString;
+
+    fn default_partition() -> Option<AwsPartition> {
+        Some(AwsPartition::Aws)
+    }
+
+    fn name() -> &'static str {
+        "AWS (strict services)"
+    }
+
+    fn resource_format_example() -> &'static str {
+        AwsEngine::resource_format_example()
+    }
+}
+
+/// An [`AwsEngine`] variant whose action matching ignores case.
+///
+/// AWS treats action names case-insensitively, so `s3:getobject` in a policy grants
+/// `s3:GetObject`. Every segment type is the same as in [`AwsEngine`], and resources are still
+/// matched case-sensitively.
+#[derive(Debug, Copy, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AwsCaseInsensitiveEngine {}
+
+impl EngineTrait for AwsCaseInsensitiveEngine {
+    type Action = WildString;
+    type Partition = AwsPartition;
+    type Service = WildString;
+    type Region = RegionPattern;
     type AccountID = WildString;
     type ResourceType = WildString;
-    type ResourceID = WildString;
+    type ResourceID = ResourceIdString;
+
+    const CASE_INSENSITIVE_ACTIONS: bool = true;
+
+    fn default_partition() -> Option<AwsPartition> {
+        Some(AwsPartition::Aws)
+    }
+
+    fn name() -> &'static str {
+        "AWS (case-insensitive actions)"
+    }
+
+    fn resource_format_example() -> &'static str {
+        AwsEngine::resource_format_example()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wildcard::Wildcard;
+
+    fn general_match(pattern: &str, value: &str) -> bool {
+        Wildcard::new(pattern.as_bytes()).unwrap().is_match(value.as_bytes())
+    }
+
+    #[test]
+    fn test_fast_paths_agree_with_general_matcher() {
+        let patterns = [
+            "*", "s3:*", "s3:Get*", "arn:aws:s3:::bucket/*", "s3:GetObject", "",
+            "s3:*Object", "s3:Get?bject*", "*:GetObject", "s3:**", "s3:\\**", "s3:Get\\*",
+            "s3:Get*Object", "s3:*Obj*", "arn:aws:s3:::*-logs-*", "arn:aws:s3:::bucket/*.log", "s3:*:",
+            "s3:Get*\\*", "s3:G*?", "s3:GetObject*GetObject",
+        ];
+        let values = [
+            "", "s3", "s3:", "s3:GetObject", "s3:PutObject", "s3:GetObjectVersion", "ec2:GetObject",
+            "arn:aws:s3:::bucket/", "arn:aws:s3:::bucket/a/b/c", "arn:aws:s3:::other/a", "s3:*", "s3:\\x", "s3:Get*",
+            "arn:aws:s3:::app-logs-2024", "arn:aws:s3:::bucket/a/app.log", "s3:GetObjectGetObject", "s3:GetObject",
+        ];
+        for pattern in patterns {
+            for value in values {
+                let fast = crate::resource_ref::glob(pattern, value);
+                assert_eq!(fast, Ok(general_match(pattern, value)), "pattern {pattern:?} value {value:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_complex_patterns_use_general_path() {
+        let pattern = WildString("s3:*Object".to_string());
+        assert_eq!(pattern.matches(&WildString("s3:GetObject".to_string())), Ok(true));
+        assert_eq!(pattern.matches(&WildString("s3:GetObjectAcl".to_string())), Ok(false));
+
+        let invalid = WildString("s3:Get\\".to_string());
+        assert!(invalid.matches(&WildString("s3:Get".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_validated_with_limits() {
+        let pattern = WildString::validated_with_limits("arn:aws:s3:::bucket/*.log", 2, 64).unwrap();
+        assert_eq!(pattern.matches(&WildString("arn:aws:s3:::bucket/app.log".to_string())), Ok(true));
+        assert!(WildString::validated_with_limits("s3:Get\\*\\?", 0, 64).is_ok());
+        assert!(WildString::validated_with_limits("s3:GetObject", 0, 12).is_ok());
+
+        let over_complex = WildString::validated_with_limits("*a*a*a*a*a*a*a*a*b", 4, 64);
+        assert_eq!(over_complex, Err(IamError::PatternTooComplex {
+            pattern: "*a*a*a*a*a*a*a*a*b".to_string(),
+            reason: "pattern has too many wildcards",
+        }));
+        assert!(matches!(
+            WildString::validated_with_limits("s3:GetObject", 0, 11),
+            Err(IamError::PatternTooComplex { reason: "pattern is too long", .. })
+        ));
+    }
+
+    #[test]
+    fn test_strict_service_engine() {
+        type Resource = crate::ResourceAbstract<AwsServiceEnumEngine>;
+        let bucket: Resource = "arn:aws:s3:::bucket".parse().unwrap();
+        assert!("arn:aws:S3:::bucket".parse::<Resource>().is_err());
+        let emails: Resource = "arn:aws:ses:us-east-1:123456789012:identity/example.com".parse().unwrap();
+        assert_eq!(emails.service, Some(AwsService::Ses));
+        assert!("arn:aws:s2:::bucket".parse::<Resource>().is_err());
+
+        let exact: Resource = "arn:aws:s3:::*".parse().unwrap();
+        let glob: Resource = "arn:aws:s3*:::*".parse().unwrap();
+        let queues: Resource = "arn:aws:sqs:::*".parse().unwrap();
+        assert_eq!(exact.matches(&bucket), Ok(true));
+        assert_eq!(glob.matches(&bucket), Ok(true));
+        assert_eq!(queues.matches(&bucket), Ok(false));
+    }
+
+    #[test]
+    fn test_case_insensitive_actions_engine() {
+        use crate::{MaybeEffect, Statement};
+
+        fn evaluate<Engine: EngineTrait<Action = WildString>>(action: &str) -> (MaybeEffect, MaybeEffect) {
+            let statement: Statement<Engine> = serde_json::from_str(
+                r#"{"effect": "allow", "actions": ["s3:getobject", "sqs:Send*"], "resources": ["arn:aws:s3:::bucket/*", "arn:aws:sqs:::*"]}"#,
+            ).unwrap();
+            let resource = "arn:aws:s3:::bucket/key".parse().unwrap();
+            let action = WildString(action.to_string());
+            (statement.matches(&action, &resource), statement.prepare().matches(&action, &resource))
+        }
+
+        for action in ["s3:GetObject", "S3:GETOBJECT"] {
+            assert_eq!(evaluate::<AwsEngine>(action), (MaybeEffect::NotSpecified, MaybeEffect::NotSpecified), "{action}");
+            assert_eq!(evaluate::<AwsCaseInsensitiveEngine>(action), (MaybeEffect::Allow, MaybeEffect::Allow), "{action}");
+        }
+        assert_eq!(evaluate::<AwsEngine>("s3:getobject"), (MaybeEffect::Allow, MaybeEffect::Allow));
+        assert_eq!(evaluate::<AwsCaseInsensitiveEngine>("s3:PutObject"), (MaybeEffect::NotSpecified, MaybeEffect::NotSpecified));
+
+        let action = WildString("SQS:SENDMESSAGE".to_string());
+        assert!(AwsCaseInsensitiveEngine::action_matches(&WildString("sqs:Send*".to_string()), &action).unwrap());
+        assert!(!AwsEngine::action_matches(&WildString("sqs:Send*".to_string()), &action).unwrap());
+    }
+
+    #[test]
+    fn test_engine_metadata() {
+        assert_eq!(AwsEngine::name(), "AWS");
+        let example = AwsEngine::resource_format_example();
+        assert!(example.parse::<crate::ResourceAbstract<AwsEngine>>().is_ok(), "{example}");
+    }
 }
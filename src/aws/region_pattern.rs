@@ -0,0 +1,162 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use crate::traits::MatchesTrait;
+use super::AwsRegion;
+
+/// A region segment pattern: one region, every region, or a family of regions sharing a code
+/// prefix.
+///
+/// This is the region type of [`AwsEngine`](super::AwsEngine). It is parsed from the region
+/// segment of an ARN:
+/// - `*` is [`RegionPattern::Any`].
+/// - `<family>-*`, where the family is one of [`RegionPattern::FAMILIES`], is
+///   [`RegionPattern::Family`], e.g. `eu-*` for every European region.
+/// - Anything else is parsed as an [`AwsRegion`] and becomes [`RegionPattern::Exact`].
+///
+/// A family matches any region whose code starts with the family followed by a dash, including
+/// region codes this crate does not know yet.
+///
+/// # Examples
+/// ```
+/// use rust_iam::aws::{AwsRegion, RegionPattern};
+/// use rust_iam::traits::MatchesTrait;
+///
+/// let europe: RegionPattern = "eu-*".parse().unwrap();
+/// assert_eq!(europe.matches(&AwsRegion::EuropeFrankfurt.into()), Ok(true));
+/// assert_eq!(europe.matches(&AwsRegion::UsEastOhio.into()), Ok(false));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionPattern {
+    /// Exactly one region.
+    Exact(AwsRegion),
+
+    /// Every region.
+    Any,
+
+    /// Every region whose code starts with `<family>-`.
+    Family(&'static str),
+}
+
+impl RegionPattern {
+    /// The region code prefixes accepted as families, e.g. `eu` in `eu-*`.
+    pub const FAMILIES: &'static [&'static str] = &["af", "ap", "ca", "eu", "il", "me", "sa", "us", "us-gov"];
+}
+
+impl From<AwsRegion> for RegionPattern {
+    fn from(region: AwsRegion) -> Self {
+        RegionPattern::Exact(region)
+    }
+}
+
+impl FromStr for RegionPattern {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "*" {
+            return Ok(RegionPattern::Any);
+        }
+        if let Some(prefix) = s.strip_suffix("-*") {
+            return RegionPattern::FAMILIES
+                .iter()
+                .find(|family| prefix.eq_ignore_ascii_case(family))
+                .map(|family| RegionPattern::Family(family))
+                .ok_or("Invalid region family");
+        }
+        AwsRegion::from_str(s).map(RegionPattern::Exact)
+    }
+}
+
+impl fmt::Display for RegionPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionPattern::Exact(region) => fmt::Display::fmt(region, f),
+            RegionPattern::Any => f.write_str("*"),
+            RegionPattern::Family(family) => write!(f, "{}-*", family),
+        }
+    }
+}
+
+impl MatchesTrait<bool> for RegionPattern {
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        match (self, value) {
+            (RegionPattern::Any, _) => Ok(true),
+            (RegionPattern::Exact(pattern), RegionPattern::Exact(region)) => pattern.matches(region),
+            (RegionPattern::Family(family), RegionPattern::Exact(region)) => Ok(region
+                .to_string()
+                .strip_prefix(family)
+                .is_some_and(|rest| rest.starts_with('-'))),
+            (RegionPattern::Family(family), RegionPattern::Family(other)) => Ok(family == other),
+            _ => Ok(false),
+        }
+    }
+}
+
+impl Serialize for RegionPattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RegionPattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        RegionPattern::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::AwsEngine;
+    use crate::ResourceAbstract;
+
+    fn region(s: &str) -> RegionPattern {
+        RegionPattern::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_family_matches_regions_by_prefix() {
+        let europe = region("eu-*");
+        assert_eq!(europe, RegionPattern::Family("eu"));
+        assert_eq!(europe.matches(&AwsRegion::EuropeFrankfurt.into()), Ok(true));
+        assert_eq!(europe.matches(&AwsRegion::EuropeIreland.into()), Ok(true));
+        assert_eq!(europe.matches(&AwsRegion::UsEastOhio.into()), Ok(false));
+        assert_eq!(europe.matches(&region("eu-future-9")), Ok(true));
+        assert_eq!(region("us-*").matches(&AwsRegion::AwsGovCloudUsWest.into()), Ok(true));
+        assert_eq!(region("us-gov-*").matches(&AwsRegion::UsEastOhio.into()), Ok(false));
+    }
+
+    #[test]
+    fn test_any_and_exact() {
+        assert_eq!(region("*"), RegionPattern::Any);
+        assert_eq!(region("*").matches(&AwsRegion::AsiaPacificTokyo.into()), Ok(true));
+        assert_eq!(region("eu-central-1"), RegionPattern::Exact(AwsRegion::EuropeFrankfurt));
+        assert_eq!(region("eu-central-1").matches(&AwsRegion::EuropeFrankfurt.into()), Ok(true));
+        assert_eq!(region("eu-central-1").matches(&AwsRegion::EuropeIreland.into()), Ok(false));
+        assert_eq!(region("eu-central-1").matches(&region("eu-*")), Ok(false));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in ["*", "eu-*", "us-gov-*", "us-east-1"] {
+            assert_eq!(region(s).to_string(), s);
+        }
+        assert_eq!(RegionPattern::from_str("xx-*"), Err("Invalid region family"));
+        assert_eq!(serde_json::to_string(&region("eu-*")).unwrap(), "\"eu-*\"");
+        assert_eq!(serde_json::from_str::<RegionPattern>("\"ap-*\"").unwrap(), RegionPattern::Family("ap"));
+    }
+
+    #[test]
+    fn test_family_in_resource_arn() {
+        let pattern: ResourceAbstract<AwsEngine> = "arn:aws:ec2:eu-*:123456789012:instance:*".parse().unwrap();
+        let instance = |region: &str| -> ResourceAbstract<AwsEngine> {
+            format!("arn:aws:ec2:{region}:123456789012:instance:i-1").parse().unwrap()
+        };
+        assert_eq!(pattern.matches(&instance("eu-central-1")), Ok(true));
+        assert_eq!(pattern.matches(&instance("eu-west-1")), Ok(true));
+        assert_eq!(pattern.matches(&instance("us-east-2")), Ok(false));
+        assert_eq!(pattern.to_string(), "arn:aws:ec2:eu-*:123456789012:instance:*");
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use crate::traits::MatchesTrait;
+use super::WildString;
+
+/// A resource identifier that compares by its percent-decoded form.
+///
+/// Resource ids such as S3 object keys are often URL-encoded on the way in, so `my%20key` and
+/// `my key` may both show up for the same object. `from_str` decodes every `%XX` escape exactly
+/// once and keeps the result as the normalized form used by equality and wildcard matching.
+/// The original input is kept for display and serialization.
+///
+/// Normalization rules:
+/// - `%` followed by two hex digits is decoded (`%20` becomes a space, `%25` becomes `%`).
+/// - Decoding happens once, so `%2520` normalizes to `%20`, not to a space.
+/// - A `%` that does not start a valid escape (e.g. `100%` or `%zz`) is kept literally.
+/// - Escapes that decode to invalid UTF-8 are rejected.
+///
+/// Decoded characters are not escaped for matching, so `%2A` in a pattern acts as `*`.
+///
+/// As in AWS, `*` is not a path glob: it spans `/`, so `bucket/*` matches
+/// `bucket/deep/nested/key`.
+#[derive(Debug, Clone)]
+pub struct ResourceIdString {
+    original: String,
+    normalized: String,
+}
+
+impl ResourceIdString {
+    /// Returns the id exactly as it was parsed.
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// Returns the percent-decoded form used for comparisons.
+    pub fn normalized(&self) -> &str {
+        &self.normalized
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+fn percent_decode(s: &str) -> Result<String, &'static str> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if let (b'%', Some(&[hi, lo])) = (bytes[i], bytes.get(i + 1..i + 3)) {
+            if let (Some(hi), Some(lo)) = (hex_value(hi), hex_value(lo)) {
+                decoded.push(hi << 4 | lo);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(decoded).map_err(|_| "percent-encoded resource id is not valid UTF-8")
+}
+
+impl FromStr for ResourceIdString {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(ResourceIdString {
+            original: s.to_string(),
+            normalized: percent_decode(s)?,
+        })
+    }
+}
+
+impl fmt::Display for ResourceIdString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.original)
+    }
+}
+
+impl PartialEq for ResourceIdString {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized == other.normalized
+    }
+}
+
+impl Eq for ResourceIdString {}
+
+impl MatchesTrait<bool> for ResourceIdString {
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        WildString(self.normalized.clone()).matches(&WildString(value.normalized.clone()))
+    }
+}
+
+impl Serialize for ResourceIdString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.original)
+    }
+}
+
+impl<'de> Deserialize<'de> for ResourceIdString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        ResourceIdString::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "with-sqlx")]
+use sqlx::{Decode, Encode, Type, Postgres};
+#[cfg(feature = "with-sqlx")]
+use serde::ser::StdError;
+
+#[cfg(feature = "with-sqlx")]
+impl<'r> Decode<'r, Postgres> for ResourceIdString {
+    fn decode(value: sqlx::postgres::PgValueRef<'r>) -> Result<Self, Box<(dyn StdError + Send + Sync + 'static)>> {
+        let decoded = <String as Decode<Postgres>>::decode(value)?;
+        Ok(ResourceIdString::from_str(&decoded)?)
+    }
+}
+
+#[cfg(feature = "with-sqlx")]
+impl Type<Postgres> for ResourceIdString {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("VARCHAR")
+    }
+}
+
+#[cfg(feature = "with-sqlx")]
+impl Encode<'_, Postgres> for ResourceIdString {
+    fn encode_by_ref(
+        &self,
+        buf: &mut sqlx::postgres::PgArgumentBuffer,
+    ) -> Result<sqlx::encode::IsNull, Box<(dyn StdError + Send + Sync + 'static)>> {
+        <std::string::String as sqlx::Encode<'_, Postgres>>::encode_by_ref(&self.original, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(s: &str) -> ResourceIdString {
+        ResourceIdString::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_encoded_and_decoded_forms_are_equivalent() {
+        let encoded = id("photos/my%20key.jpg");
+        let decoded = id("photos/my key.jpg");
+        assert_eq!(encoded, decoded);
+        assert_eq!(encoded.normalized(), "photos/my key.jpg");
+        assert_eq!(encoded.to_string(), "photos/my%20key.jpg");
+        assert_eq!(id("photos/*").matches(&encoded), Ok(true));
+        assert_eq!(id("photos/my%20*").matches(&decoded), Ok(true));
+        assert_eq!(id("%C3%A9t%C3%A9"), id("été"));
+    }
+
+    #[test]
+    fn test_literal_percent_handling() {
+        assert_eq!(id("100%").normalized(), "100%");
+        assert_eq!(id("%zz%2").normalized(), "%zz%2");
+        assert_eq!(id("50%25off").normalized(), "50%off");
+        assert_eq!(id("50%25off"), id("50%off"));
+        // Decoding happens only once.
+        assert_eq!(id("a%2520b").normalized(), "a%20b");
+        assert_ne!(id("a%2520b"), id("a b"));
+        assert!(ResourceIdString::from_str("%FF").is_err());
+    }
+
+    #[test]
+    fn test_star_spans_slashes() {
+        assert_eq!(id("bucket/*").matches(&id("bucket/deep/nested/key")), Ok(true));
+        assert_eq!(id("bucket/*/key").matches(&id("bucket/deep/nested/key")), Ok(true));
+        assert_eq!(id("bucket/*.csv").matches(&id("bucket/2024/01/report.csv")), Ok(true));
+        assert_eq!(id("bucket/?/key").matches(&id("bucket/a/b/key")), Ok(false));
+        assert_eq!(id("bucket/*").matches(&id("other/deep/key")), Ok(false));
+    }
+
+    #[test]
+    fn test_serde_preserves_original() {
+        let original = id("my%20key");
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(json, "\"my%20key\"");
+        let parsed: ResourceIdString = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.original(), "my%20key");
+    }
+}
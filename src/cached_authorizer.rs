@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+
+use crate::engine::EngineTrait;
+use crate::{MaybeEffect, PolicyCollection, PrecedenceConfig, ResourceAbstract};
+
+/// A `PolicyCollection` wrapper that memoizes authorization decisions.
+///
+/// Decisions are cached in a bounded least-recently-used map keyed by the string forms of
+/// the action and resource. Any change to the wrapped collection made through
+/// [`CachedAuthorizer::update`] or [`CachedAuthorizer::replace`] clears the cache, so a cached
+/// decision never outlives the policies that produced it.
+///
+/// The authorizer is safe to share between threads: the policies sit behind an `RwLock` and
+/// the cache behind a `Mutex`.
+///
+/// # Type Parameters
+/// - `Engine`: A type implementing the `EngineTrait`, which defines the core types and behaviors
+///   used by the wrapped policies.
+pub struct CachedAuthorizer<Engine: EngineTrait> {
+    policies: RwLock<PolicyCollection<Engine>>,
+    cache: Mutex<DecisionCache>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// An LRU map from `(action, resource)` strings to combined effects.
+///
+/// `recency` orders keys by the tick of their last use so the oldest entry can be evicted
+/// without scanning the whole map.
+struct DecisionCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<(String, String), (MaybeEffect, u64)>,
+    recency: BTreeMap<u64, (String, String)>,
+}
+
+impl DecisionCache {
+    fn new(capacity: usize) -> Self {
+        DecisionCache { capacity, tick: 0, entries: HashMap::new(), recency: BTreeMap::new() }
+    }
+
+    fn get(&mut self, key: &(String, String)) -> Option<MaybeEffect> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (effect, last_used) = self.entries.get_mut(key)?;
+        let key = self.recency.remove(last_used).expect("cache recency out of sync");
+        *last_used = tick;
+        self.recency.insert(tick, key);
+        Some(*effect)
+    }
+
+    fn insert(&mut self, key: (String, String), effect: MaybeEffect) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.recency.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(key, (effect, self.tick));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+impl<Engine: EngineTrait> CachedAuthorizer<Engine> {
+    /// Wraps `policies`, caching at most `capacity` decisions.
+    ///
+    /// A capacity of zero disables caching.
+    pub fn new(policies: PolicyCollection<Engine>, capacity: usize) -> Self {
+        CachedAuthorizer {
+            policies: RwLock::new(policies),
+            cache: Mutex::new(DecisionCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Validates whether the given action is allowed on the specified resource.
+    ///
+    /// Returns the cached decision when one exists; otherwise evaluates the wrapped
+    /// collection like [`PolicyCollection::validate`] and caches the result. With the
+    /// `with-metrics` feature, cache hits count towards `iam_decisions_total` like fresh
+    /// decisions.
+    pub fn validate(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> bool {
+        // The read lock is held until the decision is cached so that a concurrent update
+        // cannot clear the cache between evaluation and insertion.
+        let policies = self.policies.read().unwrap_or_else(|e| e.into_inner());
+        let key = (action.to_string(), resource.to_string());
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(effect) = cache.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            #[cfg(any(test, feature = "with-metrics"))]
+            crate::decision_metrics::record_decision(&effect);
+            return effect == MaybeEffect::Allow;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let effect = policies.combined_effect(action, resource, &PrecedenceConfig::default());
+        cache.insert(key, effect);
+        effect == MaybeEffect::Allow
+    }
+
+    /// Applies `f` to the wrapped collection and invalidates every cached decision.
+    pub fn update<F: FnOnce(&mut PolicyCollection<Engine>)>(&self, f: F) {
+        let mut policies = self.policies.write().unwrap_or_else(|e| e.into_inner());
+        f(&mut policies);
+        self.clear();
+    }
+
+    /// Replaces the wrapped collection and invalidates every cached decision.
+    pub fn replace(&self, policies: PolicyCollection<Engine>) {
+        self.update(|current| *current = policies);
+    }
+
+    /// Removes every cached decision.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Returns the number of decisions currently cached.
+    pub fn len(&self) -> usize {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).entries.len()
+    }
+
+    /// Returns `true` if no decisions are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of `validate` calls answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of `validate` calls that had to evaluate the policies.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::{AwsEngine, WildString};
+    use crate::Policy;
+    use std::str::FromStr;
+
+    fn policy(effect: &str, action: &str) -> Policy<AwsEngine> {
+        serde_json::from_str(&format!(
+            r#"{{"statements": [{{"effect": "{effect}", "actions": ["{action}"], "resources": ["arn:aws:s3:::*"]}}]}}"#
+        ))
+        .unwrap()
+    }
+
+    fn action(s: &str) -> WildString {
+        WildString(s.to_string())
+    }
+
+    fn bucket(name: &str) -> ResourceAbstract<AwsEngine> {
+        ResourceAbstract::from_str(&format!("arn:aws:s3:::{name}")).unwrap()
+    }
+
+    #[test]
+    fn test_repeated_checks_hit_the_cache() {
+        let authorizer = CachedAuthorizer::new(PolicyCollection(vec![policy("allow", "s3:Get*")]), 8);
+        assert!(authorizer.validate(&action("s3:GetObject"), &bucket("a")));
+        assert!(authorizer.validate(&action("s3:GetObject"), &bucket("a")));
+        assert!(!authorizer.validate(&action("s3:PutObject"), &bucket("a")));
+        assert_eq!((authorizer.hits(), authorizer.misses()), (1, 2));
+        assert_eq!(authorizer.len(), 2);
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let authorizer = CachedAuthorizer::new(PolicyCollection(vec![policy("allow", "s3:*")]), 2);
+        let get = action("s3:GetObject");
+        authorizer.validate(&get, &bucket("a"));
+        authorizer.validate(&get, &bucket("b"));
+        authorizer.validate(&get, &bucket("a"));
+        authorizer.validate(&get, &bucket("c"));
+        assert_eq!(authorizer.len(), 2);
+
+        authorizer.validate(&get, &bucket("a"));
+        assert_eq!(authorizer.hits(), 2);
+        authorizer.validate(&get, &bucket("b"));
+        assert_eq!(authorizer.hits(), 2);
+    }
+
+    #[test]
+    fn test_policy_mutation_invalidates_cache() {
+        let authorizer = CachedAuthorizer::new(PolicyCollection(vec![policy("allow", "s3:*")]), 8);
+        assert!(authorizer.validate(&action("s3:DeleteObject"), &bucket("a")));
+
+        authorizer.update(|policies| policies.push(policy("deny", "s3:Delete*")));
+        assert!(authorizer.is_empty());
+        assert!(!authorizer.validate(&action("s3:DeleteObject"), &bucket("a")));
+
+        authorizer.replace(PolicyCollection::default());
+        assert!(!authorizer.validate(&action("s3:GetObject"), &bucket("a")));
+    }
+
+    #[test]
+    fn test_clear_and_zero_capacity() {
+        let authorizer = CachedAuthorizer::new(PolicyCollection(vec![policy("allow", "s3:*")]), 8);
+        authorizer.validate(&action("s3:GetObject"), &bucket("a"));
+        authorizer.clear();
+        assert!(authorizer.is_empty());
+
+        let uncached = CachedAuthorizer::new(PolicyCollection(vec![policy("allow", "s3:*")]), 0);
+        uncached.validate(&action("s3:GetObject"), &bucket("a"));
+        uncached.validate(&action("s3:GetObject"), &bucket("a"));
+        assert_eq!((uncached.hits(), uncached.len()), (0, 0));
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt::Write;
+use crate::{Effect, EngineTrait, Policy, ResourceAbstract, Statement};
+
+/// Renders a policy as Cedar `permit`/`forbid` policies, one per statement.
+///
+/// The translation is best-effort:
+/// - Literal actions become `Action::"..."` entities; `*` leaves the action unconstrained.
+///   Other action wildcards cannot be expressed, since Cedar has no pattern matching on
+///   entity ids, so they are dropped and noted in a comment. A statement with no expressible
+///   action is emitted commented out.
+/// - A single literal resource becomes `resource == Resource::"<arn>"`; `*` leaves the
+///   resource unconstrained. Anything else becomes a `when` clause matching `resource.arn`
+///   with `like`. `?` wildcards are not supported by `like` and are noted in a comment.
+/// - Except-resources become an `unless` clause.
+pub(crate) fn policy_to_cedar<Engine: EngineTrait>(policy: &Policy<Engine>) -> String {
+    let mut output = String::new();
+    if let Some(name) = &policy.name {
+        let _ = writeln!(output, "// policy: {}", name);
+    }
+    for (index, statement) in policy.statements.iter().enumerate() {
+        if index > 0 || policy.name.is_some() {
+            output.push('\n');
+        }
+        statement_to_cedar(statement, &mut output);
+    }
+    output
+}
+
+fn statement_to_cedar<Engine: EngineTrait>(statement: &Statement<Engine>, output: &mut String) {
+    let mut notes = Vec::new();
+    let mut literal_actions = Vec::new();
+    let mut any_action = false;
+    for action in &statement.actions {
+        let action = action.to_string();
+        if action == "*" {
+            any_action = true;
+        } else if action.contains(['*', '?']) {
+            notes.push(format!("unsupported: action pattern {} cannot be expressed in Cedar", quote(&action)));
+        } else {
+            literal_actions.push(format!("Action::{}", quote(&action)));
+        }
+    }
+    let action_scope = match literal_actions.as_slice() {
+        _ if any_action => Some("action".to_string()),
+        [] => None,
+        [single] => Some(format!("action == {}", single)),
+        many => Some(format!("action in [{}]", many.join(", "))),
+    };
+
+    let resources: Vec<String> = statement.resources.iter().map(ToString::to_string).collect();
+    let (resource_scope, when) = match resources.as_slice() {
+        _ if resources.iter().any(|r| r == "*") => ("resource".to_string(), None),
+        [single] if !single.contains(['*', '?']) => (format!("resource == Resource::{}", quote(single)), None),
+        _ => ("resource".to_string(), like_any(&statement.resources, &mut notes)),
+    };
+    let unless = like_any(&statement.except_resources, &mut notes);
+
+    for note in &notes {
+        let _ = writeln!(output, "// {}", note);
+    }
+    let prefix = if action_scope.is_some() { "" } else { "// " };
+    if action_scope.is_none() {
+        let _ = writeln!(output, "// unsupported: no action could be expressed, statement omitted");
+    }
+    let effect = match statement.effect {
+        Effect::Allow => "permit",
+        Effect::Deny => "forbid",
+    };
+    let _ = writeln!(output, "{}{} (", prefix, effect);
+    let _ = writeln!(output, "{}    principal,", prefix);
+    let _ = writeln!(output, "{}    {},", prefix, action_scope.as_deref().unwrap_or("action"));
+    let _ = write!(output, "{}    {}\n{})", prefix, resource_scope, prefix);
+    if let Some(when) = when {
+        let _ = write!(output, "\n{}when {{ {} }}", prefix, when);
+    }
+    if let Some(unless) = unless {
+        let _ = write!(output, "\n{}unless {{ {} }}", prefix, unless);
+    }
+    output.push_str(";\n");
+}
+
+/// Builds a `resource.arn like "..." || ...` condition, or `None` if there are no resources.
+fn like_any<Engine: EngineTrait>(resources: &[ResourceAbstract<Engine>], notes: &mut Vec<String>) -> Option<String> {
+    let conditions: Vec<String> = resources
+        .iter()
+        .map(|resource| {
+            let pattern = resource.to_string();
+            if pattern.contains('?') {
+                notes.push(format!("unsupported: `?` in {} is matched literally by Cedar", quote(&pattern)));
+            }
+            format!("resource.arn like \"{}\"", pattern.replace('"', "\\\""))
+        })
+        .collect();
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(conditions.join(" || "))
+    }
+}
+
+/// Quotes a string as a Cedar string literal.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::AwsEngine;
+
+    fn cedar(json: &str) -> String {
+        policy_to_cedar(&serde_json::from_str::<Policy<AwsEngine>>(json).unwrap())
+    }
+
+    #[test]
+    fn test_simple_allow() {
+        let output = cedar(r#"{"statements": [
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::my-bucket"]}
+        ]}"#);
+        assert_eq!(output, concat!(
+            "permit (\n",
+            "    principal,\n",
+            "    action == Action::\"s3:GetObject\",\n",
+            "    resource == Resource::\"arn:aws:s3:::my-bucket\"\n",
+            ");\n",
+        ));
+    }
+
+    #[test]
+    fn test_patterns_and_unsupported_constructs() {
+        let output = cedar(r#"{"name": "reports", "statements": [
+            {"effect": "deny", "actions": ["s3:PutObject", "s3:DeleteObject"],
+             "resources": ["arn:aws:s3:::reports/*"], "except_resources": ["arn:aws:s3:::reports/tmp/*"]},
+            {"effect": "allow", "actions": ["s3:Get*"], "resources": ["*"]}
+        ]}"#);
+        assert_eq!(output, concat!(
+            "// policy: reports\n",
+            "\n",
+            "forbid (\n",
+            "    principal,\n",
+            "    action in [Action::\"s3:PutObject\", Action::\"s3:DeleteObject\"],\n",
+            "    resource\n",
+            ")\n",
+            "when { resource.arn like \"arn:aws:s3:::reports/*\" }\n",
+            "unless { resource.arn like \"arn:aws:s3:::reports/tmp/*\" };\n",
+            "\n",
+            "// unsupported: action pattern \"s3:Get*\" cannot be expressed in Cedar\n",
+            "// unsupported: no action could be expressed, statement omitted\n",
+            "// permit (\n",
+            "//     principal,\n",
+            "//     action,\n",
+            "//     resource\n",
+            "// );\n",
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::MaybeEffect;
+
+/// The counter incremented once per authorization decision, labelled `effect` with `allow`,
+/// `deny` or `not_specified`.
+///
+/// Every [`PolicyCollection`](crate::PolicyCollection) entry point that decides a request
+/// counts: `validate`, `validate_with`, `validate_with_mode`, `validate_parts`,
+/// `validate_request`, `validate_with_errors` and `try_matches`, as does
+/// [`CachedAuthorizer::validate`](crate::CachedAuthorizer::validate), including cache hits.
+/// Calls that end in an error (a propagated matching error or a rejected wildcard request)
+/// are not counted, and neither is analysis such as `explain` or `is_superset_of`.
+pub const DECISIONS_TOTAL: &str = "iam_decisions_total";
+
+/// Reports one combined decision to the installed `metrics` recorder.
+///
+/// Without a recorder, or without the `with-metrics` feature, nothing is recorded.
+pub(crate) fn record_decision(effect: &MaybeEffect) {
+    let effect = match effect {
+        MaybeEffect::Allow => "allow",
+        MaybeEffect::Deny => "deny",
+        MaybeEffect::NotSpecified => "not_specified",
+    };
+    metrics::counter!(DECISIONS_TOTAL, "effect" => effect).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use crate::aws::{AwsEngine, WildString};
+    use crate::{CachedAuthorizer, EvaluationMode, MatchErrorMode, PolicyCollection, ResourceAbstract};
+
+    fn policies() -> PolicyCollection<AwsEngine> {
+        serde_json::from_str(r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]},
+            {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}]"#).unwrap()
+    }
+
+    fn action(name: &str) -> WildString {
+        WildString(name.to_string())
+    }
+
+    /// Runs `f` against a fresh recorder and returns the decision counts by effect.
+    fn decision_counts(f: impl FnOnce()) -> HashMap<String, u64> {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, f);
+        snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter(|(key, ..)| key.key().name() == DECISIONS_TOTAL)
+            .map(|(key, _, _, value)| {
+                let effect = key.key().labels().find(|label| label.key() == "effect").unwrap().value().to_string();
+                let DebugValue::Counter(count) = value else { panic!("{DECISIONS_TOTAL} is not a counter") };
+                (effect, count)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_increments_decision_counters() {
+        let policies = policies();
+        let object = ResourceAbstract::from_str("arn:aws:s3:::bucket/key").unwrap();
+
+        let counts = decision_counts(|| {
+            assert!(policies.validate(&action("s3:GetObject"), &object));
+            assert!(policies.validate(&action("s3:PutObject"), &object));
+            assert!(!policies.validate(&action("s3:DeleteObject"), &object));
+            assert!(!policies.validate(&action("ec2:RunInstances"), &object));
+        });
+        assert_eq!(counts, HashMap::from([
+            ("allow".to_string(), 2),
+            ("deny".to_string(), 1),
+            ("not_specified".to_string(), 1),
+        ]));
+    }
+
+    #[test]
+    fn test_every_decision_entry_point_is_counted_once() {
+        let policies = policies();
+        let object = ResourceAbstract::from_str("arn:aws:s3:::bucket/key").unwrap();
+        let delete = action("s3:DeleteObject");
+
+        let counts = decision_counts(|| {
+            for mode in [EvaluationMode::DenyOverrides, EvaluationMode::DenyWithinPolicy, EvaluationMode::AllowOnly, EvaluationMode::MostSpecificWins] {
+                policies.validate_with_mode(&delete, &object, mode);
+            }
+            assert_eq!(policies.try_matches(&delete, &object), Ok(crate::MaybeEffect::Deny));
+            assert_eq!(policies.validate_with_errors(&delete, &object, MatchErrorMode::Propagate), Ok(false));
+            assert_eq!(policies.validate_request(&delete, &object, true), Ok(false));
+            assert!(policies.validate_request(&delete, &ResourceAbstract::from_str("arn:aws:s3:::bucket/*").unwrap(), true).is_err());
+
+            let authorizer = CachedAuthorizer::new(policies.clone(), 8);
+            assert!(!authorizer.validate(&delete, &object));
+            assert!(!authorizer.validate(&delete, &object));
+            assert_eq!(authorizer.hits(), 1);
+
+            policies.missing_permissions(&[(delete.clone(), object.clone())]);
+        });
+        // AllowOnly skips deny statements, so the s3:* grant decides
+        assert_eq!(counts, HashMap::from([
+            ("allow".to_string(), 1),
+            ("deny".to_string(), 8),
+        ]));
+    }
+}
@@ -0,0 +1,139 @@
+/// Generates a strongly-typed action enum for one service.
+///
+/// `define_actions! { S3Action, "s3", [GetObject, PutObject] }` produces an enum with one
+/// variant per listed action plus a `Pattern(String)` variant for wildcard patterns, and
+/// implements every bound an `EngineTrait::Action` needs:
+/// - `FromStr`: `"s3:GetObject"` parses to `S3Action::GetObject`. Input containing `*` or `?`
+///   (e.g. `"s3:Get*"` or `"*"`) parses to `S3Action::Pattern`. Anything else is rejected, so a
+///   typo in an action name fails at parse time instead of silently never matching.
+/// - `Display`: renders the `service:Action` form, or the pattern text.
+/// - `MatchesTrait<bool>`: wildcard matching on the rendered form, like [`WildString`](crate::aws::WildString).
+/// - `Serialize`/`Deserialize`: as the rendered string.
+///
+/// # Examples
+/// ```
+/// use rust_iam::define_actions;
+/// use rust_iam::traits::MatchesTrait;
+///
+/// define_actions! { S3Action, "s3", [GetObject, PutObject] }
+///
+/// let get: S3Action = "s3:GetObject".parse().unwrap();
+/// assert_eq!(get, S3Action::GetObject);
+/// assert_eq!(get.to_string(), "s3:GetObject");
+/// assert_eq!("s3:*".parse::<S3Action>().unwrap().matches(&get), Ok(true));
+/// assert!("s3:GetObjekt".parse::<S3Action>().is_err());
+/// ```
+#[macro_export]
+macro_rules! define_actions {
+    ($name:ident, $service:literal, [$($action:ident),* $(,)?]) => {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum $name {
+            $($action,)*
+            /// A wildcard pattern such as `s3:Get*`, kept verbatim.
+            Pattern(String),
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = &'static str;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.contains(['*', '?']) {
+                    return Ok($name::Pattern(s.to_string()));
+                }
+                match s.strip_prefix(concat!($service, ":")) {
+                    $(Some(stringify!($action)) => Ok($name::$action),)*
+                    _ => Err(concat!("Unknown ", $service, " action")),
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    $($name::$action => f.write_str(concat!($service, ":", stringify!($action))),)*
+                    $name::Pattern(pattern) => f.write_str(pattern),
+                }
+            }
+        }
+
+        impl $crate::traits::MatchesTrait<bool> for $name {
+            fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+                <$crate::aws::WildString as $crate::traits::MatchesTrait<bool>>::matches(
+                    &$crate::aws::WildString(self.to_string()),
+                    &$crate::aws::WildString(value.to_string()),
+                )
+            }
+        }
+
+        impl $crate::__private::serde::Serialize for $name {
+            fn serialize<S: $crate::__private::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::__private::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = <String as $crate::__private::serde::Deserialize>::deserialize(deserializer)?;
+                <$name as ::std::str::FromStr>::from_str(&s).map_err(<D::Error as $crate::__private::serde::de::Error>::custom)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::aws::{AwsPartition, AwsRegion, ResourceIdString, WildString};
+    use crate::engine::EngineTrait;
+    use crate::traits::MatchesTrait;
+    use crate::{MaybeEffect, Policy, ResourceAbstract};
+    use serde::{Deserialize, Serialize};
+
+    define_actions! { S3Action, "s3", [GetObject, PutObject, DeleteObject] }
+
+    #[derive(Debug, Copy, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+    struct TypedS3Engine;
+
+    impl EngineTrait for TypedS3Engine {
+        type Action = S3Action;
+        type Partition = AwsPartition;
+        type Service = WildString;
+        type Region = AwsRegion;
+        type AccountID = WildString;
+        type ResourceType = WildString;
+        type ResourceID = ResourceIdString;
+    }
+
+    #[test]
+    fn test_generated_actions_parse_and_render() {
+        assert_eq!(S3Action::from_str("s3:GetObject"), Ok(S3Action::GetObject));
+        assert_eq!(S3Action::DeleteObject.to_string(), "s3:DeleteObject");
+        assert_eq!(S3Action::from_str("s3:Get*"), Ok(S3Action::Pattern("s3:Get*".to_string())));
+        assert!(S3Action::from_str("s3:ListBucket").is_err());
+        assert!(S3Action::from_str("ec2:GetObject").is_err());
+        assert_eq!(serde_json::to_string(&S3Action::PutObject).unwrap(), "\"s3:PutObject\"");
+        assert_eq!(serde_json::from_str::<S3Action>("\"s3:PutObject\"").unwrap(), S3Action::PutObject);
+    }
+
+    #[test]
+    fn test_generated_actions_match_wildcards() {
+        let any = S3Action::from_str("s3:*").unwrap();
+        let gets = S3Action::from_str("s3:Get*").unwrap();
+        assert_eq!(any.matches(&S3Action::GetObject), Ok(true));
+        assert_eq!(any.matches(&S3Action::DeleteObject), Ok(true));
+        assert_eq!(gets.matches(&S3Action::GetObject), Ok(true));
+        assert_eq!(gets.matches(&S3Action::PutObject), Ok(false));
+        assert_eq!(S3Action::GetObject.matches(&S3Action::PutObject), Ok(false));
+
+        let policy: Policy<TypedS3Engine> = serde_json::from_str(
+            r#"{"statements": [{"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]}]}"#,
+        )
+        .unwrap();
+        let object = ResourceAbstract::from_str("arn:aws:s3:::bucket/key").unwrap();
+        assert_eq!(policy.matches(&S3Action::DeleteObject, &object), MaybeEffect::Allow);
+        assert!(serde_json::from_str::<Policy<TypedS3Engine>>(
+            r#"{"statements": [{"effect": "allow", "actions": ["s3:GetObjekt"], "resources": []}]}"#,
+        )
+        .is_err());
+    }
+}
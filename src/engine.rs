@@ -33,6 +33,18 @@ use crate::traits::MatchesTrait;
 /// - `FromStr<Err = &'static str>`: Allows the type to be parsed from a string representation.
 /// - `PartialEq`: Ensures equality comparisons can be performed.
 /// - `Clone`: Allows duplication of the value.
+///
+/// # Associated Constants
+///
+/// - `ARN_SEGMENTS`: The number of positional segments after the `arn:` prefix (default 6).
+/// - `CASE_INSENSITIVE_ACTIONS`: Whether action matching ignores ASCII case (default `false`).
+///
+/// # Provided Methods
+///
+/// - `default_partition`: The partition assumed for resources that omit one (default `None`).
+/// - `action_matches`: Matches an action pattern against an action, honouring
+///   `CASE_INSENSITIVE_ACTIONS`.
+/// - `name`, `resource_format_example`: Display metadata for tooling that lists engines.
 ///```
 pub trait EngineTrait: Debug + Default + Copy + Serialize + DeserializeOwned + Sync + Send + Clone + 'static {
     /// The type representing an action within the engine.
@@ -55,4 +67,60 @@ pub trait EngineTrait: Debug + Default + Copy + Serialize + DeserializeOwned + S
 
     /// The type representing the unique identifier for a resource.
     type ResourceID: Debug + MatchesTrait<bool> + Serialize + DeserializeOwned + FromStr<Err=&'static str> + ToString + PartialEq + Eq + Clone + Sync + Send + Clone + 'static;
+
+    /// The number of positional segments following the `arn:` prefix.
+    ///
+    /// Segments are assigned in order to the partition, service, region, account id,
+    /// resource type and resource id; an engine declaring fewer than six leaves the remaining
+    /// fields unset. The segment after this count is the qualifier, and any further segments
+    /// are kept as trailing segments. Values above six are treated as six.
+    const ARN_SEGMENTS: usize = 6;
+
+    /// Whether action matching ignores case.
+    ///
+    /// When `true`, [`EngineTrait::action_matches`] lowercases both the pattern and the action
+    /// before matching, so `s3:getobject` matches `s3:GetObject` everywhere statements are
+    /// evaluated, without wrapping actions in a dedicated type. Defaults to `false`.
+    const CASE_INSENSITIVE_ACTIONS: bool = false;
+
+    /// The partition to assume for a request resource that does not name one, when asked.
+    ///
+    /// This is opt-in: nothing in the crate applies it on its own. Parsing, `validate` and the
+    /// other evaluation entry points leave a missing partition unset, where it keeps meaning
+    /// "any partition", so a policy written without a partition still matches every partition
+    /// and a request without one only matches such policies. To pin a request to this
+    /// partition, so that policies naming it match the request too, call
+    /// [`ResourceAbstract::with_default_partition`](crate::ResourceAbstract::with_default_partition)
+    /// on it before evaluating. Returns `None` by default.
+    fn default_partition() -> Option<Self::Partition> {
+        None
+    }
+
+    /// Matches an action pattern from a statement against a requested action.
+    ///
+    /// Statement evaluation calls this instead of [`MatchesTrait::matches`] on actions, so that
+    /// [`EngineTrait::CASE_INSENSITIVE_ACTIONS`] applies crate-wide.
+    ///
+    /// # Errors
+    /// Returns an error if the pattern cannot be evaluated, or if a lowercased action no longer
+    /// parses.
+    fn action_matches(pattern: &Self::Action, action: &Self::Action) -> Result<bool, &'static str> {
+        #[cfg(test)]
+        crate::resource_ref::assert_pattern_value(&pattern.to_string(), &action.to_string());
+        if !Self::CASE_INSENSITIVE_ACTIONS {
+            return pattern.matches(action);
+        }
+        let lowercase = |a: &Self::Action| Self::Action::from_str(&a.to_string().to_lowercase());
+        lowercase(pattern)?.matches(&lowercase(action)?)
+    }
+
+    /// A short human-readable name for the engine, e.g. `"AWS"`.
+    fn name() -> &'static str {
+        "custom"
+    }
+
+    /// An example of the resource format the engine parses, for display next to [`EngineTrait::name`].
+    fn resource_format_example() -> &'static str {
+        "arn:partition:service:region:account-id:resource-type:resource-id"
+    }
 }
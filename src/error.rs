@@ -0,0 +1,106 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Errors raised while parsing or evaluating policies.
+///
+/// The infallible evaluation methods (`matches`, `validate`) resolve these with
+/// [`MatchErrorMode::FailClosed`]; the `try_` variants surface them so callers can
+/// tell a denied request apart from a broken policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IamError {
+    /// A pattern could not be compiled or compared while matching.
+    Match(&'static str),
+
+    /// One or more policy files could not be loaded. Each entry names the file and the reason.
+    Load(Vec<(PathBuf, String)>),
+
+    /// An ARN could not be parsed. `offset` is the byte offset of the offending segment and
+    /// `segment` its index, counting the `arn` prefix as segment 0.
+    MalformedArn {
+        offset: usize,
+        segment: usize,
+        reason: &'static str,
+    },
+
+    /// A request resource contained wildcard characters where a concrete resource was required.
+    WildcardRequest(String),
+
+    /// A `${...}` policy variable could not be substituted.
+    Variable {
+        name: String,
+        reason: &'static str,
+    },
+
+    /// An instantiated policy template is not a valid policy. Holds the parser's message.
+    Template(String),
+
+    /// A policy uses a feature the requested export format cannot express.
+    Unsupported(&'static str),
+
+    /// An action's service prefix is not a known service. Holds the offending action.
+    UnknownService(String),
+
+    /// A wildcard pattern exceeds the complexity limits it was validated against.
+    PatternTooComplex {
+        pattern: String,
+        reason: &'static str,
+    },
+
+    /// A hostname is not a regional AWS endpoint. Holds the hostname.
+    InvalidEndpoint(String),
+}
+
+/// How evaluation treats a statement whose patterns could not be matched.
+///
+/// Earlier releases treated every matching error as a non-match. That failed open for deny
+/// statements, since a broken deny silently stopped denying, while failing closed for allows.
+/// This inconsistency was a bug: `FailClosed` is now the default used by
+/// [`Statement::matches`](crate::Statement::matches) and
+/// [`PolicyCollection::validate`](crate::PolicyCollection::validate).
+///
+/// # Variants
+/// - `FailClosed`: An errored allow statement grants nothing and an errored deny statement
+///   denies, so a broken policy can only reduce access.
+/// - `FailOpen`: An errored allow statement grants and an errored deny statement denies
+///   nothing. Only useful for auditing what a broken policy might have intended.
+/// - `Propagate`: The error is returned to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchErrorMode {
+    #[default]
+    FailClosed,
+    FailOpen,
+    Propagate,
+}
+
+impl fmt::Display for IamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IamError::Match(reason) => write!(f, "matching failed: {}", reason),
+            IamError::Load(failures) => {
+                write!(f, "failed to load {} policy file(s)", failures.len())?;
+                for (path, reason) in failures {
+                    write!(f, "; {}: {}", path.display(), reason)?;
+                }
+                Ok(())
+            }
+            IamError::MalformedArn { offset, segment, reason } => {
+                write!(f, "malformed ARN at byte {} (segment {}): {}", offset, segment, reason)
+            }
+            IamError::WildcardRequest(resource) => write!(f, "request resource {} contains wildcards", resource),
+            IamError::Variable { name, reason } => write!(f, "cannot substitute ${{{}}}: {}", name, reason),
+            IamError::Template(reason) => write!(f, "instantiated template is not a valid policy: {}", reason),
+            IamError::Unsupported(reason) => write!(f, "cannot export policy: {}", reason),
+            IamError::UnknownService(action) => write!(f, "action {} names an unknown service", action),
+            IamError::PatternTooComplex { pattern, reason } => write!(f, "pattern {:?} is too complex: {}", pattern, reason),
+            IamError::InvalidEndpoint(host) => write!(f, "{} is not a regional AWS endpoint", host),
+        }
+    }
+}
+
+impl std::error::Error for IamError {}
+
+impl From<&'static str> for IamError {
+    fn from(reason: &'static str) -> Self {
+        IamError::Match(reason)
+    }
+}
@@ -0,0 +1,95 @@
+/// Removes `//` line comments and `/* */` block comments from a JSON document.
+///
+/// Comment markers inside string literals (including escaped quotes) are left untouched.
+/// Newlines inside comments are kept so that line numbers in later parse errors still
+/// point at the original input.
+pub(crate) fn strip_comments(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                    }
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                output.push(' ');
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::AwsEngine;
+    use crate::Policy;
+
+    #[test]
+    fn test_comments_are_removed() {
+        let input = "{\n  // a line comment\n  \"a\": 1, /* block\n comment */ \"b\": 2 // trailing\n}";
+        let value: serde_json::Value = serde_json::from_str(&strip_comments(input)).unwrap();
+        assert_eq!(value, serde_json::json!({"a": 1, "b": 2}));
+    }
+
+    #[test]
+    fn test_comment_markers_inside_strings_are_kept() {
+        let input = r#"{"url": "https://example.com//path", "note": "/* not a comment */", "q": "say \"//hi\""} // end"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_comments(input)).unwrap();
+        assert_eq!(value["url"], "https://example.com//path");
+        assert_eq!(value["note"], "/* not a comment */");
+        assert_eq!(value["q"], "say \"//hi\"");
+    }
+
+    #[test]
+    fn test_commented_policy_parses() {
+        let input = r#"{
+            // Read-only access for the reporting job
+            "name": "reports//readers",
+            "statements": [
+                /* only the reports bucket */
+                {"effect": "allow", "actions": ["s3:Get*"], "resources": ["arn:aws:s3:::reports/*"]}
+            ]
+        }"#;
+        let policy: Policy<AwsEngine> = serde_json::from_str(&strip_comments(input)).unwrap();
+        assert_eq!(policy.name.as_deref(), Some("reports//readers"));
+        assert_eq!(policy.statements.len(), 1);
+    }
+}
@@ -1,20 +1,65 @@
+// Lets `#[derive(Matches)]` refer to `::rust_iam` from inside this crate too.
+extern crate self as rust_iam;
+
 mod policy;
 pub use policy::*;
+mod policy_template;
+pub use policy_template::*;
 mod statement;
 pub use statement::*;
+mod prepared_statement;
+pub use prepared_statement::*;
 mod effect;
 pub use effect::*;
+mod precedence;
+pub use precedence::*;
 mod resource;
 pub use resource::*;
+mod resource_ref;
+pub use resource_ref::*;
+mod variables;
+pub use variables::UnknownVariable;
+mod error;
+pub use error::*;
+#[cfg(any(test, feature = "jsonc"))]
+mod jsonc;
+#[cfg(any(test, feature = "cedar"))]
+mod cedar;
+#[cfg(any(test, feature = "match-cache"))]
+mod match_cache;
+#[cfg(any(test, feature = "with-metrics"))]
+mod decision_metrics;
+#[cfg(feature = "with-metrics")]
+pub use decision_metrics::DECISIONS_TOTAL;
 pub mod aws;
 pub mod traits;
 mod policy_collection;
+mod access_universe;
+mod cached_authorizer;
+mod shared_policies;
 mod engine;
+mod define_actions;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub use policy_collection::*;
+pub use access_universe::*;
+pub use cached_authorizer::*;
+pub use shared_policies::*;
 pub use matches_macro::Matches;
 pub use engine::*;
 
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+
+    /// Wildcard matching for `#[derive(Matches)]` types marked `#[wildcard_matching]`.
+    pub fn glob(pattern: &str, value: &str) -> Result<bool, &'static str> {
+        use crate::traits::MatchesTrait;
+        crate::resource_ref::Glob(pattern).matches(&crate::resource_ref::Glob(value))
+    }
+}
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -0,0 +1,70 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use crate::resource_ref::glob;
+
+/// The most `(pattern, value)` results kept per thread before the cache is reset.
+pub(crate) const CAPACITY: usize = 4096;
+
+type Results = HashMap<(String, String), Result<bool, &'static str>>;
+
+thread_local! {
+    static RESULTS: RefCell<Results> = RefCell::new(HashMap::new());
+}
+
+/// Matches `value` against the wildcard `pattern`, memoizing the result per thread.
+///
+/// Only patterns that need the wildcard automaton are cached. Literal, `prefix*` and
+/// `prefix*suffix` patterns are cheaper to compare than to hash, and caching them made the
+/// `wild_string` benchmarks 15-35% slower. Once [`CAPACITY`] results are held, the cache is
+/// cleared rather than tracking recency, which keeps lookups cheap.
+///
+/// # Parameters
+/// - `pattern`: The wildcard pattern.
+/// - `value`: The string matched against it.
+///
+/// # Returns
+/// The same result as an uncached match.
+pub(crate) fn cached_glob(pattern: &str, value: &str) -> Result<bool, &'static str> {
+    if pattern.matches('*').count() <= 1 && !pattern.contains(['?', '\\']) {
+        return glob(pattern, value);
+    }
+    RESULTS.with(|results| {
+        let mut results = results.borrow_mut();
+        let key = (pattern.to_string(), value.to_string());
+        if let Some(result) = results.get(&key) {
+            return *result;
+        }
+        let result = glob(pattern, value);
+        if results.len() >= CAPACITY {
+            results.clear();
+        }
+        results.insert(key, result);
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_results_agree_with_uncached() {
+        let patterns = ["*", "s3:Get*", "s3:*Object", "s3:Get?bject*", "arn:aws:s3:::*-logs-*", "s3:Get\\"];
+        let values = ["s3:GetObject", "s3:PutObject", "s3:GetObjectAcl", "arn:aws:s3:::app-logs-2024", ""];
+        for _ in 0..2 {
+            for pattern in patterns {
+                for value in values {
+                    assert_eq!(cached_glob(pattern, value), glob(pattern, value), "pattern {pattern:?} value {value:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cache_is_bounded() {
+        for i in 0..CAPACITY + 10 {
+            assert_eq!(cached_glob("s3:*Get*", &format!("s3:Get{i}Object")), Ok(true));
+        }
+        RESULTS.with(|results| assert!(results.borrow().len() <= CAPACITY));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
-use serde::de::{DeserializeOwned, StdError};
-use crate::{MaybeEffect, ResourceAbstract, Statement};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "with-sqlx")]
+use serde::de::StdError;
+use crate::{AccessUniverse, Effect, IamError, MatchErrorMode, MaybeEffect, PrecedenceConfig, ResourceAbstract, Statement};
 use crate::engine::EngineTrait;
 
 /// Represents an access control policy within the system.
@@ -98,23 +100,259 @@ impl<Engine: EngineTrait> Policy<Engine> {
     /// - `MaybeEffect::NotSpecified`: No explicit allow or deny was specified.
     /// ```
     pub fn matches(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> MaybeEffect {
+        self.matches_with(action, resource, &PrecedenceConfig::default())
+    }
+
+    /// Evaluates the policy like [`Policy::matches`], combining statement effects with the
+    /// given [`PrecedenceConfig`] instead of the default AWS precedence.
+    ///
+    /// # Parameters
+    /// - `action`: The action to evaluate.
+    /// - `resource`: The resource to evaluate the action against.
+    /// - `precedence`: How conflicting statement effects are resolved.
+    ///
+    /// # Returns
+    /// - The combined `MaybeEffect` of all statements.
+    pub fn matches_with(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, precedence: &PrecedenceConfig) -> MaybeEffect {
+        // A policy without statements combines no effects and so returns `NotSpecified`
+        precedence.combine(self.statements.iter().map(|statement| statement.matches(action, resource)))
+    }
+
+    /// Parses a policy from JSON that may contain comments.
+    ///
+    /// `//` line comments and `/* */` block comments are stripped before the document is
+    /// deserialized, so annotated policy files can be loaded directly. Comment markers inside
+    /// string values are preserved. Requires the `jsonc` feature.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::Policy;
+    /// use rust_iam::aws::AwsEngine;
+    ///
+    /// let policy = Policy::<AwsEngine>::from_jsonc(r#"{
+    ///     // no statements yet
+    ///     "name": "empty",
+    ///     "statements": []
+    /// }"#).unwrap();
+    /// assert_eq!(policy.name.as_deref(), Some("empty"));
+    /// ```
+    #[cfg(feature = "jsonc")]
+    pub fn from_jsonc(input: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(&crate::jsonc::strip_comments(input))
+    }
+
+    /// Encodes the policy in the compact `bincode` format. Requires the `with-bincode` feature.
+    ///
+    /// See [`PolicyCollection::to_bincode`](crate::PolicyCollection::to_bincode).
+    #[cfg(feature = "with-bincode")]
+    pub fn to_bincode(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Decodes a policy produced by [`Policy::to_bincode`]. Requires the `with-bincode` feature.
+    #[cfg(feature = "with-bincode")]
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
+    /// Renders the policy as Cedar policy text, for migrating to Amazon Verified Permissions.
+    ///
+    /// Each statement becomes one `permit` (allow) or `forbid` (deny) policy with an
+    /// unconstrained principal. The translation is best-effort and requires the `cedar` feature:
+    /// - Cedar cannot match action ids by pattern, so action wildcards other than `*` are
+    ///   dropped and noted in a `// unsupported:` comment. A statement left without any action
+    ///   is emitted commented out rather than widened to every action.
+    /// - Resource patterns are matched against a `resource.arn` attribute with `like`, which
+    ///   the Cedar schema has to provide. `?` wildcards have no `like` equivalent.
+    /// - Except-resources become an `unless` clause.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::Policy;
+    /// use rust_iam::aws::AwsEngine;
+    ///
+    /// let policy: Policy<AwsEngine> = serde_json::from_str(r#"{"statements": [
+    ///     {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["*"]}
+    /// ]}"#).unwrap();
+    /// assert!(policy.to_cedar().starts_with("permit ("));
+    /// ```
+    #[cfg(feature = "cedar")]
+    pub fn to_cedar(&self) -> String {
+        crate::cedar::policy_to_cedar(self)
+    }
+
+    /// Evaluates the policy against a given action and resource, propagating matching errors.
+    ///
+    /// This follows the same rules as [`Policy::matches`], but stops at the first statement
+    /// whose patterns cannot be evaluated (see [`Statement::try_matches`]).
+    ///
+    /// # Returns
+    /// - `Ok(MaybeEffect)` with the same value `matches` would return for a valid policy.
+    /// - `Err(IamError::Match)` if any statement could not be evaluated.
+    pub fn try_matches(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Result<MaybeEffect, IamError> {
+        self.matches_with_errors(action, resource, MatchErrorMode::Propagate)
+    }
+
+    /// Evaluates the policy like [`Policy::matches`], resolving matching errors in each
+    /// statement according to `mode` (see [`Statement::matches_with_errors`]).
+    ///
+    /// # Returns
+    /// - `Ok(MaybeEffect)` with the combined effect of all statements.
+    /// - `Err(IamError::Match)` if `mode` is [`MatchErrorMode::Propagate`] and a statement
+    ///   could not be evaluated.
+    pub fn matches_with_errors(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, mode: MatchErrorMode) -> Result<MaybeEffect, IamError> {
         let mut is_allowed = false;
         for statement in self.statements.iter() {
-            match statement.matches(action, resource) {
+            match statement.matches_with_errors(action, resource, mode)? {
                 MaybeEffect::Allow => is_allowed = true,
-                MaybeEffect::Deny => return MaybeEffect::Deny,
-                _ => {}
+                MaybeEffect::Deny => return Ok(MaybeEffect::Deny),
+                MaybeEffect::NotSpecified => {}
             }
         }
         if is_allowed {
-            MaybeEffect::Allow
+            Ok(MaybeEffect::Allow)
         } else {
-            MaybeEffect::NotSpecified
+            Ok(MaybeEffect::NotSpecified)
+        }
+    }
+
+    /// Builds a least-privilege allow policy covering exactly the accesses in an access log.
+    ///
+    /// Entries are deduplicated and grouped by resource: the result has one `Allow` statement
+    /// per distinct resource, listing every action used on it, in the order they first appear.
+    /// Logged values are used verbatim as patterns, so the log should contain concrete
+    /// actions and ARNs; a `*` in an entry would widen the generated grant.
+    ///
+    /// # Parameters
+    /// - `entries`: The observed `(action, resource)` pairs.
+    /// - `name`: The name to give the generated policy.
+    ///
+    /// # Returns
+    /// A policy that allows every logged pair and nothing else.
+    pub fn from_access_log(entries: &[(Engine::Action, ResourceAbstract<Engine>)], name: Option<String>) -> Self
+    where
+        ResourceAbstract<Engine>: PartialEq,
+    {
+        let mut statements: Vec<Statement<Engine>> = Vec::new();
+        for (action, resource) in entries {
+            match statements.iter_mut().find(|statement| statement.resources[0] == *resource) {
+                Some(statement) => {
+                    if !statement.actions.contains(action) {
+                        statement.actions.push(action.clone());
+                    }
+                }
+                None => statements.push(Statement {
+                    effect: Effect::Allow,
+                    actions: vec![action.clone()],
+                    resources: vec![resource.clone()],
+                    except_resources: Vec::new(),
+                }),
+            }
+        }
+        Policy { name, statements }
+    }
+
+    /// Checks whether this policy grants exactly the same access as `other` over a finite universe.
+    ///
+    /// Both policies are evaluated with [`Policy::matches`] for every `(action, resource)` pair in
+    /// `universe`, and they are equivalent if every pair yields the same `MaybeEffect`. An explicit
+    /// deny and an unspecified result count as different, since they behave differently once the
+    /// policy is combined with others. The policy name is ignored.
+    ///
+    /// # Parameters
+    /// - `other`: The policy to compare against.
+    /// - `universe`: The requests to compare the policies on.
+    ///
+    /// # Returns
+    /// - `true` if both policies produce the same effect for every request in `universe`.
+    /// - `false` if at least one request is decided differently.
+    pub fn is_equivalent_to(&self, other: &Policy<Engine>, universe: &AccessUniverse<Engine>) -> bool {
+        universe
+            .pairs()
+            .all(|(action, resource)| self.matches(action, resource) == other.matches(action, resource))
+    }
+
+    /// Returns `true` if no statement of this policy can match any request.
+    ///
+    /// A statement with an empty action list or an empty resource list matches nothing, so a
+    /// policy made only of such statements, or with no statements at all, is dead weight and
+    /// can be dropped. Statements whose patterns merely happen to match nothing in practice
+    /// are not detected.
+    pub fn is_effectively_empty(&self) -> bool {
+        self.statements
+            .iter()
+            .all(|statement| statement.actions.is_empty() || statement.resources.is_empty())
+    }
+
+    /// Returns an equivalent policy with fewer statements.
+    ///
+    /// Statements with the same effect, resources and except-resources are merged into one
+    /// statement carrying the union of their actions. The result is then merged again the other
+    /// way round: statements with the same effect, actions and except-resources are combined
+    /// into one with the union of their resources. Lists are compared as sets, so their order
+    /// and duplicates do not matter. Statement effects are combined order-independently, so
+    /// the merged policy evaluates identically to the original.
+    ///
+    /// # Returns
+    /// A policy with the same name and at most as many statements.
+    pub fn compact(&self) -> Policy<Engine>
+    where
+        ResourceAbstract<Engine>: PartialEq,
+    {
+        let statements = merge_statements(
+            self.statements.clone(),
+            |a, b| same_set(&a.resources, &b.resources),
+            |into, from| union_into(&mut into.actions, from.actions),
+        );
+        let statements = merge_statements(
+            statements,
+            |a, b| same_set(&a.actions, &b.actions),
+            |into, from| union_into(&mut into.resources, from.resources),
+        );
+        Policy { name: self.name.clone(), statements }
+    }
+}
+
+/// Folds each statement into the first earlier one with the same effect and except-resources
+/// for which `same` holds, using `absorb`.
+fn merge_statements<Engine: EngineTrait>(
+    statements: Vec<Statement<Engine>>,
+    same: impl Fn(&Statement<Engine>, &Statement<Engine>) -> bool,
+    absorb: impl Fn(&mut Statement<Engine>, Statement<Engine>),
+) -> Vec<Statement<Engine>>
+where
+    ResourceAbstract<Engine>: PartialEq,
+{
+    let mut merged: Vec<Statement<Engine>> = Vec::with_capacity(statements.len());
+    for statement in statements {
+        let target = merged.iter_mut().find(|existing| {
+            existing.effect == statement.effect
+                && same_set(&existing.except_resources, &statement.except_resources)
+                && same(existing, &statement)
+        });
+        match target {
+            Some(existing) => absorb(existing, statement),
+            None => merged.push(statement),
+        }
+    }
+    merged
+}
+
+/// Returns `true` if both slices contain the same elements, ignoring order and duplicates.
+fn same_set<T: PartialEq>(a: &[T], b: &[T]) -> bool {
+    a.iter().all(|x| b.contains(x)) && b.iter().all(|x| a.contains(x))
+}
+
+/// Appends the elements of `from` that `into` does not contain yet.
+fn union_into<T: PartialEq>(into: &mut Vec<T>, from: Vec<T>) {
+    for item in from {
+        if !into.contains(&item) {
+            into.push(item);
         }
     }
 }
 
-use serde::de::{Deserializer, Error, MapAccess, Visitor};
+use serde::de::{Deserializer, Error, MapAccess, SeqAccess, Visitor};
 use std::fmt;
 impl<'de, Engine: EngineTrait + DeserializeOwned> Deserialize<'de> for Policy<Engine> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -130,6 +368,17 @@ impl<'de, Engine: EngineTrait + DeserializeOwned> Deserialize<'de> for Policy<En
                 formatter.write_str("a valid Policy object with id, name, and statements")
             }
 
+            /// Accepts the positional form produced by non-self-describing formats.
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                Ok(Policy {
+                    name: seq.next_element()?.ok_or_else(|| Error::invalid_length(0, &self))?,
+                    statements: seq.next_element()?.ok_or_else(|| Error::invalid_length(1, &self))?,
+                })
+            }
+
             fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
             where
                 M: MapAccess<'de>,
@@ -139,8 +388,18 @@ impl<'de, Engine: EngineTrait + DeserializeOwned> Deserialize<'de> for Policy<En
 
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
-                        "name" => name = Some(map.next_value()?),
-                        "statements" => statements = Some(map.next_value()?),
+                        "name" => {
+                            if name.is_some() {
+                                return Err(Error::duplicate_field("name"));
+                            }
+                            name = Some(map.next_value()?)
+                        }
+                        "statements" => {
+                            if statements.is_some() {
+                                return Err(Error::duplicate_field("statements"));
+                            }
+                            statements = Some(map.next_value()?)
+                        }
                         _ => return Err(Error::unknown_field(&key, &["name", "statements"])),
                     }
                 }
@@ -158,4 +417,138 @@ impl<'de, Engine: EngineTrait + DeserializeOwned> Deserialize<'de> for Policy<En
             PolicyVisitor(std::marker::PhantomData),
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::{AwsEngine, WildString};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_duplicate_key_is_rejected() {
+        let json = r#"{"name": "a", "statements": [], "name": "b"}"#;
+        let err = serde_json::from_str::<Policy<AwsEngine>>(json).unwrap_err();
+        assert!(err.to_string().contains("duplicate field `name`"), "{err}");
+
+        let json = r#"{"statements": [{"effect": "allow", "effect": "deny", "actions": [], "resources": []}]}"#;
+        let err = serde_json::from_str::<Policy<AwsEngine>>(json).unwrap_err();
+        assert!(err.to_string().contains("duplicate field `effect`"), "{err}");
+    }
+
+    fn policy(json: &str) -> Policy<AwsEngine> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn universe() -> AccessUniverse<AwsEngine> {
+        let actions = ["s3:GetObject", "s3:PutObject", "s3:DeleteObject", "ec2:RunInstances"]
+            .map(|a| WildString(a.to_string()));
+        let resources = ["arn:aws:s3:::bucket/a", "arn:aws:s3:::bucket/tmp/b", "arn:aws:s3:::other/c"]
+            .map(|r| ResourceAbstract::from_str(r).unwrap());
+        AccessUniverse::new(actions.to_vec(), resources.to_vec())
+    }
+
+    #[test]
+    fn test_empty_policy_is_not_specified() {
+        let empty = policy(r#"{"statements": []}"#);
+        for (action, resource) in universe().pairs() {
+            assert_eq!(empty.matches(action, resource), MaybeEffect::NotSpecified);
+            assert_eq!(empty.try_matches(action, resource), Ok(MaybeEffect::NotSpecified));
+        }
+    }
+
+    #[test]
+    fn test_is_effectively_empty() {
+        assert!(policy(r#"{"statements": []}"#).is_effectively_empty());
+
+        let empty_scopes = policy(r#"{"statements": [
+            {"effect": "allow", "actions": [], "resources": ["*"]},
+            {"effect": "deny", "actions": ["s3:*"], "resources": []}
+        ]}"#);
+        assert!(empty_scopes.is_effectively_empty());
+        assert!(universe().pairs().all(|(a, r)| empty_scopes.matches(a, r) == MaybeEffect::NotSpecified));
+
+        let normal = policy(r#"{"statements": [
+            {"effect": "allow", "actions": [], "resources": ["*"]},
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}"#);
+        assert!(!normal.is_effectively_empty());
+    }
+
+    #[test]
+    fn test_compact_merges_statements() {
+        let original = policy(r#"{"name": "verbose", "statements": [
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]},
+            {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::bucket/tmp/*"]},
+            {"effect": "allow", "actions": ["s3:PutObject", "s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]},
+            {"effect": "allow", "actions": ["s3:GetObject", "s3:PutObject"], "resources": ["arn:aws:s3:::other/*"]},
+            {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::other/c"]},
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"],
+             "except_resources": ["arn:aws:s3:::bucket/tmp/*"]}
+        ]}"#);
+        let compacted = original.compact();
+
+        assert_eq!(compacted.name.as_deref(), Some("verbose"));
+        assert_eq!(compacted.statements.len(), 3);
+        assert!(compacted.statements.len() < original.statements.len());
+        assert!(compacted.is_equivalent_to(&original, &universe()));
+        assert_eq!(compacted.statements[0].actions.len(), 2);
+        assert_eq!(compacted.statements[0].resources.len(), 2);
+        assert_eq!(compacted.statements[1].resources.len(), 2);
+        assert_eq!(compacted.statements[2].except_resources.len(), 1);
+        assert_eq!(compacted.compact(), compacted);
+    }
+
+    #[test]
+    fn test_reordered_and_deduplicated_policy_is_equivalent() {
+        let original = policy(r#"{"name": "v1", "statements": [
+            {"effect": "allow", "actions": ["s3:GetObject", "s3:PutObject"], "resources": ["arn:aws:s3:::bucket/*"]},
+            {"effect": "deny", "actions": ["s3:PutObject"], "resources": ["arn:aws:s3:::bucket/tmp/*"]},
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}"#);
+        let refactored = policy(r#"{"name": "v2", "statements": [
+            {"effect": "deny", "actions": ["s3:PutObject"], "resources": ["arn:aws:s3:::bucket/tmp/*"]},
+            {"effect": "allow", "actions": ["s3:PutObject", "s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}"#);
+        assert!(original.is_equivalent_to(&refactored, &universe()));
+        assert!(refactored.is_equivalent_to(&original, &universe()));
+    }
+
+    #[test]
+    fn test_weakened_policy_is_not_equivalent() {
+        let original = policy(r#"{"statements": [
+            {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]},
+            {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}"#);
+        // The deny was narrowed to a prefix, so deletes outside tmp/ are now allowed.
+        let weakened = policy(r#"{"statements": [
+            {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]},
+            {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::bucket/tmp/*"]}
+        ]}"#);
+        assert!(!original.is_equivalent_to(&weakened, &universe()));
+        assert!(original.is_equivalent_to(&weakened, &AccessUniverse::new(Vec::new(), Vec::new())));
+    }
+
+    #[test]
+    fn test_from_access_log_covers_exactly_the_log() {
+        let entry = |a: &str, r: &str| (WildString(a.to_string()), ResourceAbstract::<AwsEngine>::from_str(r).unwrap());
+        let log = [
+            entry("s3:GetObject", "arn:aws:s3:::bucket/a"),
+            entry("s3:PutObject", "arn:aws:s3:::bucket/a"),
+            entry("s3:GetObject", "arn:aws:s3:::bucket/a"),
+            entry("s3:GetObject", "arn:aws:s3:::bucket/b"),
+        ];
+        let policy = Policy::from_access_log(&log, Some("observed".to_string()));
+
+        assert_eq!(policy.name.as_deref(), Some("observed"));
+        assert_eq!(policy.statements.len(), 2);
+        assert_eq!(policy.statements[0].actions.len(), 2);
+        for (action, resource) in &log {
+            assert_eq!(policy.matches(action, resource), MaybeEffect::Allow);
+        }
+        let (action, resource) = entry("s3:PutObject", "arn:aws:s3:::bucket/b");
+        assert_eq!(policy.matches(&action, &resource), MaybeEffect::NotSpecified);
+        let (action, resource) = entry("s3:GetObject", "arn:aws:s3:::bucket/c");
+        assert_eq!(policy.matches(&action, &resource), MaybeEffect::NotSpecified);
+    }
+}
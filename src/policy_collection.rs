@@ -1,5 +1,8 @@
-use crate::{MaybeEffect, Policy, ResourceAbstract};
+use crate::{AccessUniverse, Effect, IamError, MatchErrorMode, MaybeEffect, Policy, PrecedenceConfig, ResourceAbstract, Statement};
 use crate::engine::EngineTrait;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 /// A collection of policies that determine access control for resources based on actions.
 ///
@@ -47,9 +50,21 @@ where
     }
 }
 
-use serde::de::{Deserialize, Deserializer, SeqAccess, StdError, Visitor};
+use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+#[cfg(feature = "with-sqlx")]
+use serde::de::StdError;
+use serde::{Serialize, Serializer};
 use std::fmt;
 
+impl<Engine: EngineTrait> Serialize for PolicyCollection<Engine> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
 impl<'de, Engine: EngineTrait> Deserialize<'de> for PolicyCollection<Engine> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -64,6 +79,8 @@ impl<'de, Engine: EngineTrait> Deserialize<'de> for PolicyCollection<Engine> {
                 formatter.write_str("a list of policies")
             }
 
+            /// Any element that fails to parse fails the whole collection (fail closed); see
+            /// [`PolicyCollection::from_json_lenient`] for the skipping alternative.
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
             where
                 A: SeqAccess<'de>,
@@ -129,6 +146,100 @@ impl<Engine: EngineTrait> sea_orm::sea_query::ValueType for PolicyCollection<Eng
     }
 }
 
+/// Controls how the decisions of individual policies are combined by
+/// [`PolicyCollection::validate_with_mode`].
+///
+/// # Variants
+/// - `DenyOverrides`: AWS semantics. An explicit deny in any policy vetoes allows from every
+///   policy in the collection. This is what [`PolicyCollection::validate`] uses.
+/// - `DenyWithinPolicy`: Deny-overrides is scoped to each policy document. A deny in policy A
+///   vetoes allows in A, but does not affect an allow coming from policy B. The request is
+///   allowed if at least one policy, evaluated on its own, allows it.
+/// - `AllowOnly`: Additive mode. Deny statements are ignored and the request is allowed if any
+///   allow statement matches. Evaluation stops at the first matching allow, which is only sound
+///   because no later deny can override it.
+/// - `MostSpecificWins`: Among the statements that apply, the one with the highest
+///   [`Statement::specificity`] decides, so a narrow allow can override a broad deny. When
+///   the most specific statements disagree, deny wins.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EvaluationMode {
+    /// An explicit deny anywhere in the collection wins.
+    #[default]
+    DenyOverrides,
+
+    /// An explicit deny only vetoes allows from the same policy.
+    DenyWithinPolicy,
+
+    /// Deny statements are ignored; the first matching allow decides.
+    AllowOnly,
+
+    /// The most specific applying statement decides.
+    MostSpecificWins,
+}
+
+/// What would have to change for a request to be allowed, as returned by
+/// [`PolicyCollection::remediation`].
+///
+/// Statements are identified by `(policy index, statement index)` within the collection.
+///
+/// # Variants
+/// - `NotNeeded`: The request is already allowed.
+/// - `EditDenies`: One or more deny statements match the request. They must be edited (or the
+///   request carved out of them) before anything else helps. `has_allow` tells whether an allow
+///   statement already matches, i.e. whether editing the denies alone is enough.
+/// - `AddAllow`: No statement denies the request, but none allows it either. `near_misses`
+///   lists allow statements that match the action or the resource but not both, which are the
+///   natural candidates to widen; it is empty if a new statement is needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Remediation {
+    /// The request is already allowed.
+    NotNeeded,
+
+    /// Matching deny statements block the request.
+    EditDenies { denies: Vec<(usize, usize)>, has_allow: bool },
+
+    /// Nothing allows the request.
+    AddAllow { near_misses: Vec<(usize, usize)> },
+}
+
+/// A statement that matched a request, as listed in a [`Decision`].
+///
+/// # Fields
+/// - `policy`, `statement`: The `(policy index, statement index)` of the statement within the
+///   collection. Statements have no SID, so indices identify them.
+/// - `policy_name`: The name of the policy the statement belongs to, if it has one.
+/// - `effect`: The effect the statement applied to the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementOutcome {
+    pub policy: usize,
+    pub statement: usize,
+    pub policy_name: Option<String>,
+    pub effect: Effect,
+}
+
+/// The result of [`PolicyCollection::explain`]: the decision and every statement behind it.
+///
+/// # Fields
+/// - `allowed`: The same result [`PolicyCollection::validate`] returns.
+/// - `outcomes`: Every statement that matched the request, in document order (by policy, then
+///   by statement), so reviewers can follow the whole chain rather than only the winner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    pub allowed: bool,
+    pub outcomes: Vec<StatementOutcome>,
+}
+
+impl Decision {
+    /// Returns the statement that decided the request: the first matching deny if there is
+    /// one, otherwise the first matching allow, or `None` for an implicit deny.
+    pub fn deciding(&self) -> Option<&StatementOutcome> {
+        self.outcomes
+            .iter()
+            .find(|outcome| outcome.effect == Effect::Deny)
+            .or_else(|| self.outcomes.first())
+    }
+}
+
 impl<Engine: EngineTrait> Deref for PolicyCollection<Engine> {
     type Target = Vec<Policy<Engine>>;
     fn deref(&self) -> &Self::Target {
@@ -168,6 +279,23 @@ impl<Engine: EngineTrait> Extend<Policy<Engine>> for PolicyCollection<Engine> {
 }
 
 impl<Engine: EngineTrait> PolicyCollection<Engine> {
+    /// Encodes the collection in the compact `bincode` format, e.g. for caching in Redis.
+    ///
+    /// This goes through the same serde impls as JSON, with resources stored as ARN strings.
+    /// Requires the `with-bincode` feature.
+    #[cfg(feature = "with-bincode")]
+    pub fn to_bincode(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Decodes a collection produced by [`PolicyCollection::to_bincode`].
+    ///
+    /// Requires the `with-bincode` feature.
+    #[cfg(feature = "with-bincode")]
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+
     /// Validates whether the given action is allowed on the specified resource.
     ///
     /// This method evaluates all policies in the collection to determine the effect (`MaybeEffect`)
@@ -177,6 +305,7 @@ impl<Engine: EngineTrait> PolicyCollection<Engine> {
     /// 2. If no denial is found but at least one policy explicitly allows the action, the method
     ///    returns `true`.
     /// 3. If neither allow nor deny is specified by any policy, the method returns `false`.
+    ///    In particular, an empty collection denies everything.
     ///
     /// # Parameters
     /// - `action`: The action to validate (e.g., `Read`, `Write`).
@@ -187,14 +316,1204 @@ impl<Engine: EngineTrait> PolicyCollection<Engine> {
     /// - `false` if the action is explicitly denied or not explicitly allowed.
     /// ```
     pub fn validate(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> bool {
-        let mut is_allowed = false;
+        self.validate_with(action, resource, &PrecedenceConfig::default())
+    }
+
+    /// Validates whether the given action is allowed on the specified resource, resolving
+    /// conflicting effects with the given [`PrecedenceConfig`].
+    ///
+    /// With `PrecedenceConfig::default()` this is identical to [`PolicyCollection::validate`].
+    /// With the `with-metrics` feature, each call increments the `iam_decisions_total` counter
+    /// labelled with the combined effect.
+    ///
+    /// # Parameters
+    /// - `action`: The action to validate.
+    /// - `resource`: The resource to validate the action against.
+    /// - `precedence`: How conflicting effects are resolved and what an unspecified result means.
+    ///
+    /// # Returns
+    /// - `true` if the combined effect is allowed under `precedence`.
+    /// - `false` otherwise.
+    pub fn validate_with(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, precedence: &PrecedenceConfig) -> bool {
+        precedence.is_allowed(self.combined_effect(action, resource, precedence))
+    }
+
+    /// Combines every policy's effect under `precedence` and records the decision.
+    pub(crate) fn combined_effect(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, precedence: &PrecedenceConfig) -> MaybeEffect {
+        let effect = precedence.combine(self.0.iter().map(|policy| policy.matches_with(action, resource, precedence)));
+        #[cfg(any(test, feature = "with-metrics"))]
+        crate::decision_metrics::record_decision(&effect);
+        effect
+    }
+
+    /// Like [`PolicyCollection::validate`], but without recording a decision, for analyses
+    /// that evaluate many hypothetical requests.
+    fn allows(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> bool {
+        let precedence = PrecedenceConfig::default();
+        precedence.is_allowed(precedence.combine(self.0.iter().map(|policy| policy.matches_with(action, resource, &precedence))))
+    }
+
+    /// Validates whether the given action is allowed on the specified resource using the
+    /// given [`EvaluationMode`].
+    ///
+    /// With `EvaluationMode::DenyOverrides` this is identical to [`PolicyCollection::validate`].
+    /// With `EvaluationMode::DenyWithinPolicy` each policy is evaluated on its own (so its
+    /// denies still override its own allows), and the action is allowed if any policy allows it.
+    /// With `EvaluationMode::AllowOnly` deny statements are skipped and the scan returns at the
+    /// first allow statement that matches.
+    /// With `EvaluationMode::MostSpecificWins` every statement is scored and the highest
+    /// scoring ones decide, with deny breaking ties.
+    ///
+    /// # Parameters
+    /// - `action`: The action to validate.
+    /// - `resource`: The resource to validate the action against.
+    /// - `mode`: How per-policy decisions are combined.
+    ///
+    /// # Returns
+    /// - `true` if the action is allowed under the chosen mode.
+    /// - `false` otherwise.
+    pub fn validate_with_mode(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, mode: EvaluationMode) -> bool {
+        let effect = match mode {
+            EvaluationMode::DenyOverrides => return self.validate(action, resource),
+            EvaluationMode::DenyWithinPolicy => {
+                let mut effect = MaybeEffect::NotSpecified;
+                for policy in &self.0 {
+                    match policy.matches(action, resource) {
+                        MaybeEffect::Allow => {
+                            effect = MaybeEffect::Allow;
+                            break;
+                        }
+                        MaybeEffect::Deny => effect = MaybeEffect::Deny,
+                        MaybeEffect::NotSpecified => {}
+                    }
+                }
+                effect
+            }
+            EvaluationMode::AllowOnly => {
+                let allowed = self.statements().any(|(_, statement)| {
+                    statement.effect == Effect::Allow && statement.matches(action, resource) == MaybeEffect::Allow
+                });
+                if allowed { MaybeEffect::Allow } else { MaybeEffect::NotSpecified }
+            }
+            EvaluationMode::MostSpecificWins => {
+                let winner = self
+                    .statements()
+                    .filter_map(|(_, statement)| {
+                        let specificity = statement.specificity(action, resource)?;
+                        // Deny sorts after allow, so it wins among equally specific statements
+                        Some((specificity, statement.effect == Effect::Deny))
+                    })
+                    .max();
+                match winner {
+                    Some((_, true)) => MaybeEffect::Deny,
+                    Some((_, false)) => MaybeEffect::Allow,
+                    None => MaybeEffect::NotSpecified,
+                }
+            }
+        };
+        #[cfg(any(test, feature = "with-metrics"))]
+        crate::decision_metrics::record_decision(&effect);
+        effect == MaybeEffect::Allow
+    }
+
+    /// Validates an action against a resource given as already-parsed ARN segments.
+    ///
+    /// This is equivalent to building the ARN string and calling [`PolicyCollection::validate`]
+    /// with its parsed form, but skips the format/parse round trip. Pass `None` for segments
+    /// that are empty in the ARN (e.g. the region and account of an S3 bucket).
+    ///
+    /// # Parameters
+    /// - `action`: The action to validate.
+    /// - `partition`, `service`, `region`, `account_id`, `resource_type`, `resource_id`:
+    ///   The segments of the requested resource.
+    ///
+    /// # Returns
+    /// - `true` if the action is allowed and not denied by any policy.
+    /// - `false` otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn validate_parts(
+        &self,
+        action: &Engine::Action,
+        partition: Option<Engine::Partition>,
+        service: Option<Engine::Service>,
+        region: Option<Engine::Region>,
+        account_id: Option<Engine::AccountID>,
+        resource_type: Option<Engine::ResourceType>,
+        resource_id: Option<Engine::ResourceID>,
+    ) -> bool {
+        let resource = ResourceAbstract {
+            partition,
+            service,
+            region,
+            account_id,
+            resource_type,
+            resource_id,
+            qualifier: None,
+            trailing: Vec::new(),
+        };
+        self.validate(action, &resource)
+    }
+
+    /// Evaluates all policies against the given action and resource, propagating matching errors.
+    ///
+    /// Unlike [`PolicyCollection::validate`], which treats a pattern that fails to compile as a
+    /// non-match, this method returns an error so callers can tell "denied" apart from
+    /// "broken policy". An explicit deny from any policy wins over allows.
+    ///
+    /// # Returns
+    /// - `Ok(MaybeEffect::Deny)` if any policy explicitly denies the action.
+    /// - `Ok(MaybeEffect::Allow)` if no policy denies and at least one allows it.
+    /// - `Ok(MaybeEffect::NotSpecified)` if no policy mentions the action.
+    /// - `Err(IamError::Match)` if any policy could not be evaluated.
+    pub fn try_matches(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Result<MaybeEffect, IamError> {
+        self.matches_with_errors(action, resource, MatchErrorMode::Propagate)
+    }
+
+    /// Validates a request like [`PolicyCollection::validate`], optionally refusing request
+    /// resources that contain wildcards.
+    ///
+    /// A request should name one concrete resource. A wildcard in it usually comes from a buggy
+    /// upstream, and matching it segment by segment treats both sides as patterns. With
+    /// `reject_wildcard_requests` set, such a request is an error instead of being evaluated.
+    ///
+    /// # Returns
+    /// - `Ok(bool)` with the result of [`PolicyCollection::validate`].
+    /// - `Err(IamError::WildcardRequest)` if `reject_wildcard_requests` is set and `resource`
+    ///   is not [concrete](ResourceAbstract::is_concrete).
+    pub fn validate_request(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, reject_wildcard_requests: bool) -> Result<bool, IamError> {
+        if reject_wildcard_requests && !resource.is_concrete() {
+            return Err(IamError::WildcardRequest(resource.to_string()));
+        }
+        Ok(self.validate(action, resource))
+    }
+
+    /// Validates whether the given action is allowed on the specified resource, resolving
+    /// statements whose patterns cannot be evaluated according to `mode`.
+    ///
+    /// [`PolicyCollection::validate`] behaves like this method with
+    /// [`MatchErrorMode::FailClosed`], so a broken deny statement still denies.
+    ///
+    /// # Parameters
+    /// - `action`: The action to validate.
+    /// - `resource`: The resource to validate the action against.
+    /// - `mode`: What an errored statement evaluates to.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if the action is allowed and not denied by any policy.
+    /// - `Ok(false)` if it is denied or not explicitly allowed.
+    /// - `Err(IamError::Match)` if `mode` is [`MatchErrorMode::Propagate`] and a statement
+    ///   could not be evaluated.
+    pub fn validate_with_errors(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, mode: MatchErrorMode) -> Result<bool, IamError> {
+        Ok(self.matches_with_errors(action, resource, mode)? == MaybeEffect::Allow)
+    }
+
+    fn matches_with_errors(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>, mode: MatchErrorMode) -> Result<MaybeEffect, IamError> {
+        let mut effect = MaybeEffect::NotSpecified;
         for policy in &self.0 {
-            match policy.matches(action, resource) {
-                MaybeEffect::Allow => { is_allowed = true }
-                MaybeEffect::Deny => { return false }
+            match policy.matches_with_errors(action, resource, mode)? {
+                MaybeEffect::Allow => effect = MaybeEffect::Allow,
+                MaybeEffect::Deny => {
+                    effect = MaybeEffect::Deny;
+                    break;
+                }
                 MaybeEffect::NotSpecified => {}
             }
         }
-        is_allowed
+        #[cfg(any(test, feature = "with-metrics"))]
+        crate::decision_metrics::record_decision(&effect);
+        Ok(effect)
+    }
+
+    /// Loads every `.json` policy file in a directory into one collection.
+    ///
+    /// Each file must contain a single policy. Files are read in file-name order so the
+    /// resulting collection is deterministic; files with other extensions and subdirectories
+    /// are ignored.
+    ///
+    /// # Returns
+    /// - `Ok(PolicyCollection)` if every policy file was parsed.
+    /// - `Err(IamError::Load)` listing each file that could not be read or parsed (or the
+    ///   directory itself, if it could not be listed).
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Self, IamError> {
+        let dir = path.as_ref();
+        let entries = fs::read_dir(dir).map_err(|e| IamError::Load(vec![(dir.to_path_buf(), e.to_string())]))?;
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+
+        let mut policies = Vec::with_capacity(files.len());
+        let mut failures = Vec::new();
+        for file in files {
+            let parsed = fs::read_to_string(&file)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| serde_json::from_str::<Policy<Engine>>(&contents).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(policy) => policies.push(policy),
+                Err(reason) => failures.push((file, reason)),
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(PolicyCollection(policies))
+        } else {
+            Err(IamError::Load(failures))
+        }
+    }
+
+    /// Parses a JSON array of policies, rejecting the whole collection if any policy fails.
+    ///
+    /// This is the fail-closed behaviour of the `Deserialize` impl: a single unparseable policy
+    /// or statement is an error, so a broken document never silently loses a deny.
+    pub fn from_json(input: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(input)
+    }
+
+    /// Parses a JSON array of policies, keeping the ones that parse and reporting the rest.
+    ///
+    /// Skipping a policy can silently drop a deny, so only use this where availability matters
+    /// more than failing closed, and surface the returned warnings.
+    ///
+    /// # Returns
+    /// - `Ok((collection, skipped))` where `skipped` lists the index and parse error of every
+    ///   element that is not a valid policy.
+    /// - `Err` if `input` is not a JSON array at all.
+    pub fn from_json_lenient(input: &str) -> Result<(Self, Vec<(usize, String)>), serde_json::Error> {
+        let elements: Vec<serde_json::Value> = serde_json::from_str(input)?;
+        let mut policies = Vec::with_capacity(elements.len());
+        let mut skipped = Vec::new();
+        for (index, element) in elements.into_iter().enumerate() {
+            match serde_json::from_value::<Policy<Engine>>(element) {
+                Ok(policy) => policies.push(policy),
+                Err(e) => skipped.push((index, e.to_string())),
+            }
+        }
+        Ok((PolicyCollection(policies), skipped))
+    }
+
+    /// Iterates over every statement in the collection together with the policy that owns it.
+    ///
+    /// Statements are yielded in document order: all statements of the first policy, then
+    /// those of the second, and so on. The owning policy is included so analysis code can
+    /// report context such as the policy name alongside each statement.
+    pub fn statements(&self) -> impl Iterator<Item = (&Policy<Engine>, &Statement<Engine>)> {
+        self.0
+            .iter()
+            .flat_map(|policy| policy.statements.iter().map(move |statement| (policy, statement)))
+    }
+
+    /// Lists every action and resource pattern pair under a `Deny` statement, for auditing
+    /// guardrails.
+    ///
+    /// Each deny statement contributes one pair per combination of its actions and resources.
+    /// `except_resources` are not reported: they carve holes into the deny rather than
+    /// extending it, so review them separately on the owning statement.
+    ///
+    /// # Returns
+    /// The denied `(action, resource)` patterns, in document order and then by action and
+    /// resource within each statement.
+    pub fn denied_patterns(&self) -> Vec<(&Engine::Action, &ResourceAbstract<Engine>)> {
+        self.statements()
+            .filter(|(_, statement)| statement.effect == Effect::Deny)
+            .flat_map(|(_, statement)| {
+                statement
+                    .actions
+                    .iter()
+                    .flat_map(move |action| statement.resources.iter().map(move |resource| (action, resource)))
+            })
+            .collect()
+    }
+
+    /// Lists every service the collection refers to, for "which services does this touch?"
+    /// audits.
+    ///
+    /// The service of an action is the text before its first `:`, and the service of a
+    /// resource is its service segment. Both allow and deny statements are included. An action
+    /// without a `:` (such as `*`) and a resource without a service segment (such as `*`) can
+    /// refer to any service, and are reported as the sentinel `*`.
+    ///
+    /// # Returns
+    /// The distinct service names, sorted.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::PolicyCollection;
+    /// use rust_iam::aws::AwsEngine;
+    ///
+    /// let policies: PolicyCollection<AwsEngine> = serde_json::from_str(r#"[
+    ///     {"statements": [{"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:kms:us-east-1:123456789012:key/k"]}]}
+    /// ]"#).unwrap();
+    /// assert_eq!(policies.services().into_iter().collect::<Vec<_>>(), ["kms", "s3"]);
+    /// ```
+    pub fn services(&self) -> BTreeSet<String> {
+        let mut services = BTreeSet::new();
+        for (_, statement) in self.statements() {
+            for action in &statement.actions {
+                let action = action.to_string();
+                let service = action.split_once(':').map_or("*", |(service, _)| service);
+                services.insert(service.to_string());
+            }
+            for resource in &statement.resources {
+                services.insert(resource.service.as_ref().map_or_else(|| "*".to_string(), ToString::to_string));
+            }
+        }
+        services
+    }
+
+    /// Checks whether any action at all is allowed on the given resource.
+    ///
+    /// The action side is treated as a universal match: every `Allow` statement that applies to
+    /// `resource` contributes its action patterns, and the resource counts as exposed if at least
+    /// one of those patterns is not covered by a `Deny` statement that also applies to `resource`.
+    /// A deny pattern covers an allow pattern when it matches the allow pattern's text, so a
+    /// deny of `s3:*` covers an allow of `s3:Get*`, while a deny of `s3:Get*` does not cover `s3:*`.
+    ///
+    /// # Parameters
+    /// - `resource`: The resource to check.
+    ///
+    /// # Returns
+    /// - `true` if some allowed action on `resource` survives the applicable denies.
+    /// - `false` if nothing allows access to `resource`, or every allow is denied.
+    pub fn allows_any_action(&self, resource: &ResourceAbstract<Engine>) -> bool {
+        let applicable: Vec<&Statement<Engine>> = self
+            .statements()
+            .map(|(_, statement)| statement)
+            .filter(|statement| statement.applies_to_resource(resource))
+            .collect();
+        let denied: Vec<&Engine::Action> = applicable
+            .iter()
+            .filter(|statement| statement.effect == Effect::Deny)
+            .flat_map(|statement| statement.actions.iter())
+            .collect();
+
+        applicable
+            .iter()
+            .filter(|statement| statement.effect == Effect::Allow)
+            .flat_map(|statement| statement.actions.iter())
+            .any(|allowed| !denied.iter().any(|deny| matches!(Engine::action_matches(deny, allowed), Ok(true))))
+    }
+
+    /// Evaluates `action` on `resource` and lists every statement that matched, in document order.
+    ///
+    /// # Parameters
+    /// - `action`: The requested action.
+    /// - `resource`: The requested resource.
+    ///
+    /// # Returns
+    /// A [`Decision`] whose `allowed` flag agrees with [`PolicyCollection::validate`].
+    pub fn explain(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Decision {
+        let mut outcomes = Vec::new();
+        for (p, policy) in self.0.iter().enumerate() {
+            for (s, statement) in policy.statements.iter().enumerate() {
+                let effect = match statement.matches(action, resource) {
+                    MaybeEffect::Allow => Effect::Allow,
+                    MaybeEffect::Deny => Effect::Deny,
+                    MaybeEffect::NotSpecified => continue,
+                };
+                outcomes.push(StatementOutcome { policy: p, statement: s, policy_name: policy.name.clone(), effect });
+            }
+        }
+        let allowed = !outcomes.is_empty() && outcomes.iter().all(|outcome| outcome.effect == Effect::Allow);
+        Decision { allowed, outcomes }
+    }
+
+    /// Explains what would have to change for `action` on `resource` to be allowed.
+    ///
+    /// # Parameters
+    /// - `action`: The requested action.
+    /// - `resource`: The requested resource.
+    ///
+    /// # Returns
+    /// A [`Remediation`] naming the deny statements to edit, or the allow statements closest to
+    /// granting the request when an allow is missing.
+    pub fn remediation(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Remediation {
+        let mut denies = Vec::new();
+        let mut allows = Vec::new();
+        let mut near_misses = Vec::new();
+        for (p, policy) in self.0.iter().enumerate() {
+            for (s, statement) in policy.statements.iter().enumerate() {
+                match statement.matches(action, resource) {
+                    MaybeEffect::Deny => denies.push((p, s)),
+                    MaybeEffect::Allow => allows.push((p, s)),
+                    MaybeEffect::NotSpecified if statement.effect == Effect::Allow => {
+                        let action_matches = statement.actions.iter().any(|a| matches!(Engine::action_matches(a, action), Ok(true)));
+                        if action_matches || statement.applies_to_resource(resource) {
+                            near_misses.push((p, s));
+                        }
+                    }
+                    MaybeEffect::NotSpecified => {}
+                }
+            }
+        }
+
+        if !denies.is_empty() {
+            Remediation::EditDenies { denies, has_allow: !allows.is_empty() }
+        } else if allows.is_empty() {
+            Remediation::AddAllow { near_misses }
+        } else {
+            Remediation::NotNeeded
+        }
+    }
+
+    /// Computes the effective permissions granted by the collection over a candidate universe.
+    ///
+    /// Every combination of the supplied `actions` and `resources` is evaluated with
+    /// [`PolicyCollection::validate`], and the pairs that end up allowed (after explicit
+    /// denies have been applied) are returned. This is the materialized access set, which is
+    /// useful for access reviews and for comparing what two roles can actually do.
+    ///
+    /// # Parameters
+    /// - `actions`: The candidate actions to evaluate.
+    /// - `resources`: The candidate resources to evaluate.
+    ///
+    /// # Returns
+    /// The allowed `(action, resource)` pairs, ordered by action and then by resource.
+    pub fn effective_permissions(
+        &self,
+        actions: &[Engine::Action],
+        resources: &[ResourceAbstract<Engine>],
+    ) -> Vec<(Engine::Action, ResourceAbstract<Engine>)> {
+        let mut permissions = Vec::new();
+        for action in actions {
+            for resource in resources {
+                if self.allows(action, resource) {
+                    permissions.push((action.clone(), resource.clone()));
+                }
+            }
+        }
+        permissions
+    }
+
+    /// Finds the required permissions that the collection does not grant.
+    ///
+    /// Each pair in `required` is evaluated with [`PolicyCollection::validate`], and the pairs
+    /// that are not allowed (including those explicitly denied) are returned. Keeping the list
+    /// of permissions a role must have next to its policies turns them into a testable
+    /// requirement: an empty result means every required permission is granted.
+    ///
+    /// # Parameters
+    /// - `required`: The `(action, resource)` pairs that must be allowed.
+    ///
+    /// # Returns
+    /// The pairs from `required` that are not allowed, in their original order.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::{PolicyCollection, ResourceAbstract};
+    /// use rust_iam::aws::{AwsEngine, WildString};
+    ///
+    /// let policies: PolicyCollection<AwsEngine> = serde_json::from_str(r#"[
+    ///     {"statements": [{"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]}]}
+    /// ]"#).unwrap();
+    /// let object: ResourceAbstract<AwsEngine> = "arn:aws:s3:::bucket/key".parse().unwrap();
+    /// let required = [
+    ///     (WildString("s3:GetObject".to_string()), object.clone()),
+    ///     (WildString("s3:PutObject".to_string()), object.clone()),
+    /// ];
+    /// let missing = policies.missing_permissions(&required);
+    /// assert_eq!(missing, vec![(WildString("s3:PutObject".to_string()), object)]);
+    /// ```
+    pub fn missing_permissions(
+        &self,
+        required: &[(Engine::Action, ResourceAbstract<Engine>)],
+    ) -> Vec<(Engine::Action, ResourceAbstract<Engine>)> {
+        required
+            .iter()
+            .filter(|(action, resource)| !self.allows(action, resource))
+            .cloned()
+            .collect()
+    }
+
+    /// Exports every statement of the collection as one AWS IAM policy document.
+    ///
+    /// AWS attaches a single document per attachment and has no notion of nested policies, so
+    /// the statements of all policies are merged, in collection order, under one `Statement`
+    /// array with `Version` `2012-10-17`. Policy names are lost in this export. The combined
+    /// decision is unchanged, since AWS evaluates all statements of a document together with
+    /// an explicit deny winning, like [`PolicyCollection::validate`].
+    ///
+    /// # Errors
+    /// Returns `IamError::Unsupported` if a statement has `except_resources`, which AWS cannot
+    /// combine with `Resource` in one statement.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::PolicyCollection;
+    /// use rust_iam::aws::AwsEngine;
+    ///
+    /// let policies: PolicyCollection<AwsEngine> = serde_json::from_str(r#"[
+    ///     {"name": "read", "statements": [{"effect": "allow", "actions": ["s3:GetObject"], "resources": ["*"]}]}
+    /// ]"#).unwrap();
+    /// let document = policies.to_aws_json().unwrap();
+    /// assert_eq!(document["Statement"][0]["Effect"], "Allow");
+    /// ```
+    pub fn to_aws_json(&self) -> Result<serde_json::Value, IamError> {
+        let statements = self
+            .statements()
+            .map(|(_, statement)| {
+                if !statement.except_resources.is_empty() {
+                    return Err(IamError::Unsupported("except_resources has no AWS equivalent"));
+                }
+                let effect = match statement.effect {
+                    Effect::Allow => "Allow",
+                    Effect::Deny => "Deny",
+                };
+                let actions: Vec<String> = statement.actions.iter().map(ToString::to_string).collect();
+                let resources: Vec<String> = statement.resources.iter().map(ToString::to_string).collect();
+                Ok(serde_json::json!({ "Effect": effect, "Action": actions, "Resource": resources }))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(serde_json::json!({ "Version": "2012-10-17", "Statement": statements }))
+    }
+
+    /// Checks whether this collection allows everything `other` allows over a finite universe.
+    ///
+    /// Both collections are evaluated with [`PolicyCollection::validate`] for every
+    /// `(action, resource)` pair in `universe`. This collection may allow more. Use it to
+    /// confirm that an edit is purely additive, i.e. that the new policies never take away
+    /// access the old ones granted.
+    ///
+    /// # Parameters
+    /// - `other`: The collection whose allowed requests must all stay allowed.
+    /// - `universe`: The requests to compare the collections on.
+    ///
+    /// # Returns
+    /// - `true` if every request in `universe` allowed by `other` is also allowed by `self`.
+    /// - `false` if `other` allows at least one request that `self` does not.
+    pub fn is_superset_of(&self, other: &PolicyCollection<Engine>, universe: &AccessUniverse<Engine>) -> bool {
+        universe
+            .pairs()
+            .all(|(action, resource)| !other.allows(action, resource) || self.allows(action, resource))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::{AwsEngine, AwsPartition, WildString};
+    use std::str::FromStr;
+
+    fn collection(json: &str) -> PolicyCollection<AwsEngine> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn action(s: &str) -> WildString {
+        WildString(s.to_string())
+    }
+
+    fn resource(s: &str) -> ResourceAbstract<AwsEngine> {
+        ResourceAbstract::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_to_aws_json_merges_policies() {
+        let policies = collection(r#"[
+            {"name": "readers", "statements": [
+                {"effect": "allow", "actions": ["s3:GetObject", "s3:List*"], "resources": ["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/*"]}
+            ]},
+            {"name": "guard", "statements": [
+                {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["*"]}
+            ]}
+        ]"#);
+        let document = policies.to_aws_json().unwrap();
+        assert_eq!(document, serde_json::json!({
+            "Version": "2012-10-17",
+            "Statement": [
+                {"Effect": "Allow", "Action": ["s3:GetObject", "s3:List*"], "Resource": ["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/*"]},
+                {"Effect": "Deny", "Action": ["s3:DeleteObject"], "Resource": ["*"]}
+            ]
+        }));
+        assert!(!document.to_string().contains("readers"));
+
+        let carved = collection(r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:*"], "resources": ["*"], "except_resources": ["arn:aws:s3:::secret/*"]}
+        ]}]"#);
+        assert!(matches!(carved.to_aws_json(), Err(IamError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_denied_patterns_only_lists_denies() {
+        let policies = collection(r#"[
+            {"name": "app", "statements": [
+                {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]},
+                {"effect": "deny", "actions": ["s3:DeleteObject", "s3:PutObjectAcl"], "resources": [
+                    "arn:aws:s3:::bucket/*", "arn:aws:s3:::logs/*"
+                ], "except_resources": ["arn:aws:s3:::logs/tmp/*"]}
+            ]},
+            {"name": "guardrails", "statements": [
+                {"effect": "allow", "actions": ["sqs:*"], "resources": ["*"]},
+                {"effect": "deny", "actions": ["iam:*"], "resources": ["*"]}
+            ]}
+        ]"#);
+        let denied: Vec<(String, String)> = policies
+            .denied_patterns()
+            .into_iter()
+            .map(|(action, resource)| (action.to_string(), resource.to_string()))
+            .collect();
+        let expected = [
+            ("s3:DeleteObject", "arn:aws:s3:::bucket/*"),
+            ("s3:DeleteObject", "arn:aws:s3:::logs/*"),
+            ("s3:PutObjectAcl", "arn:aws:s3:::bucket/*"),
+            ("s3:PutObjectAcl", "arn:aws:s3:::logs/*"),
+            ("iam:*", "*"),
+        ];
+        assert_eq!(denied, expected.map(|(a, r)| (a.to_string(), r.to_string())));
+
+        let allow_only = collection(r#"[{"statements": [{"effect": "allow", "actions": ["*"], "resources": ["*"]}]}]"#);
+        assert!(allow_only.denied_patterns().is_empty());
+    }
+
+    #[test]
+    fn test_services_across_policies() {
+        let policies = collection(r#"[
+            {"name": "app", "statements": [
+                {"effect": "allow", "actions": ["s3:GetObject", "s3:List*"], "resources": ["arn:aws:s3:::bucket/*"]},
+                {"effect": "allow", "actions": ["sqs:SendMessage", "kms:Decrypt"], "resources": [
+                    "arn:aws:sqs:us-east-1:123456789012:queue", "arn:aws:kms:us-east-1:123456789012:key/k"
+                ]}
+            ]},
+            {"name": "guard", "statements": [
+                {"effect": "deny", "actions": ["dynamodb:DeleteTable"], "resources": ["arn:aws:dynamodb:us-east-1:123456789012:table/t"]}
+            ]}
+        ]"#);
+        let services: Vec<String> = policies.services().into_iter().collect();
+        assert_eq!(services, ["dynamodb", "kms", "s3", "sqs"]);
+
+        let admin = collection(r#"[{"statements": [{"effect": "allow", "actions": ["*"], "resources": ["*"]}]}]"#);
+        assert_eq!(admin.services().into_iter().collect::<Vec<_>>(), ["*"]);
+        assert!(PolicyCollection::<AwsEngine>::default().services().is_empty());
+    }
+
+    #[test]
+    fn test_missing_permissions() {
+        let policies = collection(r#"[
+            {"name": "app", "statements": [
+                {"effect": "allow", "actions": ["s3:GetObject", "s3:PutObject"], "resources": ["arn:aws:s3:::bucket/*"]},
+                {"effect": "allow", "actions": ["sqs:SendMessage"], "resources": ["*"]}
+            ]},
+            {"name": "guard", "statements": [
+                {"effect": "deny", "actions": ["s3:PutObject"], "resources": ["arn:aws:s3:::bucket/locked/*"]}
+            ]}
+        ]"#);
+        let required = [
+            (action("s3:GetObject"), resource("arn:aws:s3:::bucket/key")),
+            (action("s3:PutObject"), resource("arn:aws:s3:::bucket/locked/key")),
+            (action("sqs:SendMessage"), resource("arn:aws:sqs:us-east-1:123456789012:queue")),
+            (action("s3:DeleteObject"), resource("arn:aws:s3:::bucket/key")),
+            (action("s3:GetObject"), resource("arn:aws:s3:::other/key")),
+        ];
+        assert_eq!(policies.missing_permissions(&required), vec![
+            (action("s3:PutObject"), resource("arn:aws:s3:::bucket/locked/key")),
+            (action("s3:DeleteObject"), resource("arn:aws:s3:::bucket/key")),
+            (action("s3:GetObject"), resource("arn:aws:s3:::other/key")),
+        ]);
+        assert!(policies.missing_permissions(&required[..1]).is_empty());
+        assert_eq!(PolicyCollection::<AwsEngine>::default().missing_permissions(&required), required.to_vec());
+    }
+
+    #[test]
+    fn test_is_superset_of() {
+        let universe = AccessUniverse::new(
+            vec![action("s3:GetObject"), action("s3:PutObject"), action("s3:DeleteObject")],
+            vec![resource("arn:aws:s3:::bucket/key"), resource("arn:aws:s3:::other/key")],
+        );
+        let readers = collection(r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}]"#);
+        let writers = collection(r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:Get*", "s3:PutObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}]"#);
+        let others = collection(r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::other/*"]}
+        ]}]"#);
+
+        // Strict superset
+        assert!(writers.is_superset_of(&readers, &universe));
+        assert!(!readers.is_superset_of(&writers, &universe));
+
+        // Equal access, written differently
+        let readers_again = collection(r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:Get*"], "resources": ["arn:aws:s3:::bucket/*"]},
+            {"effect": "deny", "actions": ["s3:GetObjectAcl"], "resources": ["*"]}
+        ]}]"#);
+        assert!(readers.is_superset_of(&readers_again, &universe));
+        assert!(readers_again.is_superset_of(&readers, &universe));
+
+        // Incomparable
+        assert!(!others.is_superset_of(&readers, &universe));
+        assert!(!readers.is_superset_of(&others, &universe));
+    }
+
+    #[test]
+    fn test_explain_lists_matching_statements_in_order() {
+        let policies = collection(r#"[
+            {"name": "base", "statements": [
+                {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]},
+                {"effect": "allow", "actions": ["ec2:*"], "resources": ["*"]},
+                {"effect": "deny", "actions": ["s3:Delete*"], "resources": ["arn:aws:s3:::bucket/*"]}
+            ]},
+            {"statements": [
+                {"effect": "allow", "actions": ["s3:DeleteObject"], "resources": ["*"]}
+            ]}
+        ]"#);
+        let object = resource("arn:aws:s3:::bucket/key");
+        let decision = policies.explain(&action("s3:DeleteObject"), &object);
+
+        let outcome = |policy, statement, name: Option<&str>, effect| StatementOutcome {
+            policy,
+            statement,
+            policy_name: name.map(str::to_string),
+            effect,
+        };
+        assert!(!decision.allowed);
+        assert_eq!(decision.outcomes, vec![
+            outcome(0, 0, Some("base"), Effect::Allow),
+            outcome(0, 2, Some("base"), Effect::Deny),
+            outcome(1, 0, None, Effect::Allow),
+        ]);
+        assert_eq!(decision.deciding(), Some(&decision.outcomes[1]));
+
+        let read = policies.explain(&action("s3:GetObject"), &object);
+        assert!(read.allowed);
+        assert_eq!(read.deciding().map(|o| (o.policy, o.statement)), Some((0, 0)));
+
+        let nothing = policies.explain(&action("iam:CreateUser"), &object);
+        assert_eq!(nothing, Decision { allowed: false, outcomes: vec![] });
+        assert_eq!(nothing.deciding(), None);
+    }
+
+    #[test]
+    fn test_strict_and_lenient_parsing() {
+        let json = r#"[
+            {"name": "good", "statements": [{"effect": "allow", "actions": ["s3:*"], "resources": ["*"]}]},
+            {"name": "bad", "statements": [{"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:ec2:zz:1:instance:i-1"]}]},
+            {"name": "also good", "statements": [{"effect": "deny", "actions": ["s3:Delete*"], "resources": ["*"]}]},
+            {"name": "typo", "statments": []}
+        ]"#;
+        assert!(PolicyCollection::<AwsEngine>::from_json(json).is_err());
+        assert!(serde_json::from_str::<PolicyCollection<AwsEngine>>(json).is_err());
+
+        let (policies, skipped) = PolicyCollection::<AwsEngine>::from_json_lenient(json).unwrap();
+        let names: Vec<_> = policies.iter().map(|p| p.name.as_deref().unwrap()).collect();
+        assert_eq!(names, ["good", "also good"]);
+        assert_eq!(skipped.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [1, 3]);
+        assert!(skipped[1].1.contains("statments"), "{}", skipped[1].1);
+
+        assert!(PolicyCollection::<AwsEngine>::from_json_lenient("{}").is_err());
+    }
+
+    #[test]
+    fn test_wildcard_requests_can_be_rejected() {
+        let policies = collection(r#"[
+            {"statements": [{"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::public/*"]}]}
+        ]"#);
+        let get = action("s3:GetObject");
+        let wildcard = resource("arn:aws:s3:::public/*");
+
+        assert!(policies.validate(&get, &wildcard));
+        assert_eq!(policies.validate_request(&get, &wildcard, false), Ok(true));
+        assert_eq!(
+            policies.validate_request(&get, &wildcard, true),
+            Err(IamError::WildcardRequest("arn:aws:s3:::public/*".to_string()))
+        );
+        assert!(policies.validate_request(&get, &resource("arn:aws:s3:::p?blic/key"), true).is_err());
+        assert_eq!(policies.validate_request(&get, &resource("arn:aws:s3:::public/key"), true), Ok(true));
+        assert_eq!(policies.validate_request(&get, &resource("arn:aws:s3:::private/key"), true), Ok(false));
+    }
+
+    #[test]
+    fn test_empty_collection_denies_everything() {
+        let empty: PolicyCollection<AwsEngine> = PolicyCollection(Vec::new());
+        let bucket = resource("arn:aws:s3:::my-bucket");
+        for a in ["s3:GetObject", "*"] {
+            assert!(!empty.validate(&action(a), &bucket));
+            assert_eq!(empty.try_matches(&action(a), &bucket), Ok(MaybeEffect::NotSpecified));
+        }
+        for mode in [
+            EvaluationMode::DenyOverrides,
+            EvaluationMode::DenyWithinPolicy,
+            EvaluationMode::AllowOnly,
+            EvaluationMode::MostSpecificWins,
+        ] {
+            assert!(!empty.validate_with_mode(&action("s3:GetObject"), &bucket, mode));
+        }
+        let no_statements = collection(r#"[{"statements": []}]"#);
+        assert!(!no_statements.validate(&action("s3:GetObject"), &bucket));
+    }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let policies = collection(r#"[
+            {"name": "objects", "statements": [
+                {"effect": "allow", "actions": ["s3:Get*", "s3:PutObject"], "resources": ["arn:aws:s3:::bucket/*"],
+                 "except_resources": ["arn:aws:s3:::bucket/private/*"]},
+                {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["*"]}
+            ]},
+            {"statements": [
+                {"effect": "allow", "actions": ["ec2:*"], "resources": ["arn:aws:ec2:eu-*:123456789012:instance:i-1"]}
+            ]}
+        ]"#);
+        let bytes = bincode::serialize(&policies).unwrap();
+        assert!(bytes.len() < serde_json::to_vec(&policies).unwrap().len());
+        let decoded: PolicyCollection<AwsEngine> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded, policies);
+
+        let json = serde_json::to_string(&policies.0[0].statements[1]).unwrap();
+        assert!(!json.contains("except_resources"), "{json}");
+    }
+
+    #[test]
+    fn test_effective_permissions_with_deny_holes() {
+        let policies = collection(r#"[
+            {"name": "broad", "statements": [
+                {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:us-east-1:123456789012:bucket:*"]}
+            ]},
+            {"name": "guardrail", "statements": [
+                {"effect": "deny", "actions": ["s3:Delete*"], "resources": ["arn:aws:s3:us-east-1:123456789012:bucket:prod"]}
+            ]}
+        ]"#);
+        let actions = [action("s3:GetObject"), action("s3:DeleteObject"), action("ec2:RunInstances")];
+        let resources = [
+            resource("arn:aws:s3:us-east-1:123456789012:bucket:dev"),
+            resource("arn:aws:s3:us-east-1:123456789012:bucket:prod"),
+        ];
+
+        assert_eq!(
+            policies.effective_permissions(&actions, &resources),
+            vec![
+                (action("s3:GetObject"), resources[0].clone()),
+                (action("s3:GetObject"), resources[1].clone()),
+                (action("s3:DeleteObject"), resources[0].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_effective_permissions_empty_universe() {
+        let policies = collection(r#"[{"statements": [
+            {"effect": "allow", "actions": ["*"], "resources": ["arn:aws:s3:us-east-1:123456789012:bucket:*"]}
+        ]}]"#);
+        assert!(policies.effective_permissions(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_try_matches_distinguishes_broken_policy() {
+        let policies = collection(r#"[
+            {"statements": [{"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::my-bucket"]}]},
+            {"statements": [{"effect": "deny", "actions": ["s3:Delete\\"], "resources": ["arn:aws:s3:::my-bucket"]}]}
+        ]"#);
+        let bucket = resource("arn:aws:s3:::my-bucket");
+
+        assert!(!policies.validate(&action("s3:DeleteObject"), &bucket));
+        assert!(matches!(policies.try_matches(&action("s3:DeleteObject"), &bucket), Err(IamError::Match(_))));
+        assert_eq!(policies.try_matches(&action("s3:GetObject"), &resource("arn:aws:s3:::other")), Ok(MaybeEffect::NotSpecified));
+    }
+
+    #[test]
+    fn test_validate_with_errors_modes() {
+        let policies = collection(r#"[
+            {"statements": [{"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::my-bucket"]}]},
+            {"statements": [{"effect": "deny", "actions": ["s3:Delete\\"], "resources": ["arn:aws:s3:::my-bucket"]}]},
+            {"statements": [{"effect": "allow", "actions": ["ec2:Run\\"], "resources": ["arn:aws:s3:::my-bucket"]}]}
+        ]"#);
+        let bucket = resource("arn:aws:s3:::my-bucket");
+        let get = action("s3:GetObject");
+        let run = action("ec2:RunInstances");
+
+        assert_eq!(policies.validate_with_errors(&get, &bucket, MatchErrorMode::FailClosed), Ok(false));
+        assert_eq!(policies.validate_with_errors(&run, &bucket, MatchErrorMode::FailClosed), Ok(false));
+        assert_eq!(policies.validate_with_errors(&get, &bucket, MatchErrorMode::FailOpen), Ok(true));
+        assert_eq!(policies.validate_with_errors(&run, &bucket, MatchErrorMode::FailOpen), Ok(true));
+        assert!(matches!(policies.validate_with_errors(&get, &bucket, MatchErrorMode::Propagate), Err(IamError::Match(_))));
+        assert!(!policies.validate(&get, &bucket));
+    }
+
+    #[test]
+    fn test_statements_across_policies() {
+        let policies = collection(r#"[
+            {"name": "first", "statements": [
+                {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::a"]},
+                {"effect": "allow", "actions": ["s3:PutObject"], "resources": ["arn:aws:s3:::a"]}
+            ]},
+            {"name": "second", "statements": [
+                {"effect": "deny", "actions": ["s3:*"], "resources": ["arn:aws:s3:::b"]}
+            ]}
+        ]"#);
+
+        let names: Vec<_> = policies.statements().map(|(policy, _)| policy.name.as_deref().unwrap()).collect();
+        assert_eq!(names, ["first", "first", "second"]);
+        assert_eq!(policies.statements().filter(|(_, s)| s.effect == crate::Effect::Deny).count(), 1);
+    }
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("rust-iam-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn write(&self, file: &str, contents: &str) {
+            fs::write(self.0.join(file), contents).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const VALID: &str = r#"{"name": "NAME", "statements": [{"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::b"]}]}"#;
+
+    #[test]
+    fn test_from_dir_loads_json_files_in_order() {
+        let dir = TempDir::new("from-dir-valid");
+        dir.write("b.json", &VALID.replace("NAME", "second"));
+        dir.write("a.json", &VALID.replace("NAME", "first"));
+        dir.write("notes.txt", "not a policy");
+
+        let policies = PolicyCollection::<AwsEngine>::from_dir(&dir.0).unwrap();
+        let names: Vec<_> = policies.iter().map(|p| p.name.as_deref().unwrap()).collect();
+        assert_eq!(names, ["first", "second"]);
+    }
+
+    #[test]
+    fn test_from_dir_reports_every_invalid_file() {
+        let dir = TempDir::new("from-dir-invalid");
+        dir.write("good.json", &VALID.replace("NAME", "good"));
+        dir.write("broken.json", "{ not json");
+        dir.write("wrong-shape.json", r#"{"statements": "nope"}"#);
+
+        let Err(IamError::Load(failures)) = PolicyCollection::<AwsEngine>::from_dir(&dir.0) else {
+            panic!("expected a load error");
+        };
+        let files: Vec<_> = failures.iter().map(|(path, _)| path.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(files, ["broken.json", "wrong-shape.json"]);
+    }
+
+    #[test]
+    fn test_from_dir_missing_directory() {
+        let missing = std::env::temp_dir().join("rust-iam-does-not-exist");
+        assert!(matches!(PolicyCollection::<AwsEngine>::from_dir(missing), Err(IamError::Load(f)) if f.len() == 1));
+    }
+
+    #[test]
+    fn test_deny_within_policy_mode() {
+        let policies = collection(r#"[
+            {"name": "team", "statements": [
+                {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::shared"]},
+                {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::shared"]}
+            ]},
+            {"name": "admin", "statements": [
+                {"effect": "allow", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::shared"]}
+            ]},
+            {"name": "lockdown", "statements": [
+                {"effect": "deny", "actions": ["s3:PutObject"], "resources": ["arn:aws:s3:::shared"]}
+            ]}
+        ]"#);
+        let shared = resource("arn:aws:s3:::shared");
+        let check = |a: &str, mode| policies.validate_with_mode(&action(a), &shared, mode);
+
+        // The team policy's own deny cannot veto the admin policy's allow.
+        assert!(!check("s3:DeleteObject", EvaluationMode::DenyOverrides));
+        assert!(check("s3:DeleteObject", EvaluationMode::DenyWithinPolicy));
+
+        // The lockdown deny vetoes the team allow globally but not within its own scope.
+        assert!(!check("s3:PutObject", EvaluationMode::DenyOverrides));
+        assert!(check("s3:PutObject", EvaluationMode::DenyWithinPolicy));
+
+        assert!(check("s3:GetObject", EvaluationMode::DenyOverrides));
+        assert!(check("s3:GetObject", EvaluationMode::DenyWithinPolicy));
+        assert_eq!(check("s3:GetObject", EvaluationMode::default()), policies.validate(&action("s3:GetObject"), &shared));
+    }
+
+    #[test]
+    fn test_most_specific_wins_mode() {
+        let policies = collection(r#"[
+            {"name": "lockdown", "statements": [
+                {"effect": "deny", "actions": ["s3:*"], "resources": ["arn:aws:s3:::reports/*"]}
+            ]},
+            {"name": "exceptions", "statements": [
+                {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::reports/public.csv"]},
+                {"effect": "allow", "actions": ["s3:PutObject"], "resources": ["arn:aws:s3:::reports/*"]}
+            ]}
+        ]"#);
+        let public = resource("arn:aws:s3:::reports/public.csv");
+        let check = |a: &str, r: &ResourceAbstract<AwsEngine>, mode| policies.validate_with_mode(&action(a), r, mode);
+
+        // The literal allow beats the broad deny only under most-specific-wins.
+        assert!(!check("s3:GetObject", &public, EvaluationMode::DenyOverrides));
+        assert!(check("s3:GetObject", &public, EvaluationMode::MostSpecificWins));
+
+        // The allow and the deny are equally broad on the resource, but the allow names the action.
+        assert!(check("s3:PutObject", &public, EvaluationMode::MostSpecificWins));
+
+        // Nothing is more specific than the deny here.
+        assert!(!check("s3:DeleteObject", &public, EvaluationMode::MostSpecificWins));
+        assert!(!check("s3:GetObject", &resource("arn:aws:s3:::reports/private.csv"), EvaluationMode::MostSpecificWins));
+    }
+
+    #[test]
+    fn test_most_specific_wins_breaks_ties_with_deny() {
+        let policies = collection(r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::reports/*"]},
+            {"effect": "deny", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::reports/*"]}
+        ]}]"#);
+        let object = resource("arn:aws:s3:::reports/q1.csv");
+        assert!(!policies.validate_with_mode(&action("s3:GetObject"), &object, EvaluationMode::MostSpecificWins));
+    }
+
+    #[test]
+    fn test_validate_parts_agrees_with_validate() {
+        let policies = collection(r#"[
+            {"name": "ec2", "statements": [
+                {"effect": "allow", "actions": ["ec2:*"], "resources": ["arn:aws:ec2::123456789012:instance:*"]},
+                {"effect": "deny", "actions": ["ec2:TerminateInstances"], "resources": ["arn:aws:ec2::123456789012:instance:i-prod*"]}
+            ]},
+            {"name": "s3", "statements": [
+                {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+            ]}
+        ]"#);
+        let cases = [
+            ("ec2:StartInstances", "arn:aws:ec2:us-east-1:123456789012:instance:i-prod1"),
+            ("ec2:TerminateInstances", "arn:aws:ec2:us-east-1:123456789012:instance:i-prod1"),
+            ("ec2:TerminateInstances", "arn:aws:ec2:us-east-1:123456789012:instance:i-dev1"),
+            ("ec2:StartInstances", "arn:aws:ec2:us-east-1:999999999999:instance:i-dev1"),
+            ("s3:GetObject", "arn:aws:s3:::bucket/key"),
+            ("s3:GetObject", "arn:aws:s3:::other/key"),
+        ];
+        for (a, arn) in cases {
+            let parsed = resource(arn);
+            let by_parts = policies.validate_parts(
+                &action(a),
+                parsed.partition.clone(),
+                parsed.service.clone(),
+                parsed.region.clone(),
+                parsed.account_id.clone(),
+                parsed.resource_type.clone(),
+                parsed.resource_id.clone(),
+            );
+            assert_eq!(by_parts, policies.validate(&action(a), &parsed), "{a} on {arn}");
+        }
+
+        assert!(policies.validate_parts(
+            &action("s3:GetObject"),
+            Some(AwsPartition::Aws),
+            Some(WildString("s3".to_string())),
+            None,
+            None,
+            Some(WildString("bucket/key".to_string())),
+            None,
+        ));
+    }
+
+    #[test]
+    fn test_validate_with_allow_wins_precedence() {
+        let policies = collection(r#"[
+            {"statements": [
+                {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]},
+                {"effect": "deny", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]}
+            ]}
+        ]"#);
+        let object = resource("arn:aws:s3:::bucket/key");
+        let allow_wins = PrecedenceConfig { deny_wins: false, ..Default::default() };
+
+        assert!(!policies.validate(&action("s3:GetObject"), &object));
+        assert!(policies.validate_with(&action("s3:GetObject"), &object, &allow_wins));
+        assert!(!policies.validate_with(&action("s3:PutObject"), &object, &allow_wins));
+        assert!(!policies.validate_with(&action("ec2:RunInstances"), &object, &allow_wins));
+
+        let open_by_default = PrecedenceConfig { default_allow: true, ..Default::default() };
+        assert!(policies.validate_with(&action("ec2:RunInstances"), &object, &open_by_default));
+        assert!(!policies.validate_with(&action("s3:PutObject"), &object, &open_by_default));
+    }
+
+    #[test]
+    fn test_allows_any_action() {
+        let policies = collection(r#"[
+            {"statements": [
+                {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::public/*"]},
+                {"effect": "allow", "actions": ["s3:Get*"], "resources": ["arn:aws:s3:::locked/*"]},
+                {"effect": "deny", "actions": ["s3:*"], "resources": ["arn:aws:s3:::locked/*", "arn:aws:s3:::private/*"]},
+                {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::partial/*"]},
+                {"effect": "deny", "actions": ["s3:Delete*"], "resources": ["arn:aws:s3:::partial/*"]}
+            ]}
+        ]"#);
+        assert!(policies.allows_any_action(&resource("arn:aws:s3:::public/key")));
+        assert!(policies.allows_any_action(&resource("arn:aws:s3:::partial/key")));
+        assert!(!policies.allows_any_action(&resource("arn:aws:s3:::locked/key")));
+        assert!(!policies.allows_any_action(&resource("arn:aws:s3:::private/key")));
+        assert!(!policies.allows_any_action(&resource("arn:aws:s3:::unknown/key")));
+    }
+
+    #[test]
+    fn test_allow_only_mode_agrees_with_full_scan() {
+        let policies = collection(r#"[
+            {"statements": [
+                {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]},
+                {"effect": "deny", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]}
+            ]},
+            {"statements": [
+                {"effect": "allow", "actions": ["s3:PutObject"], "resources": ["arn:aws:s3:::bucket/uploads/*"]},
+                {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/uploads/*"]}
+            ]}
+        ]"#);
+        let full_scan = |a: &WildString, r: &ResourceAbstract<AwsEngine>| {
+            policies
+                .statements()
+                .filter(|(_, statement)| statement.effect == Effect::Allow)
+                .fold(false, |allowed, (_, statement)| allowed | (statement.matches(a, r) == MaybeEffect::Allow))
+        };
+        for a in ["s3:GetObject", "s3:PutObject", "s3:DeleteObject", "ec2:RunInstances"] {
+            for r in ["arn:aws:s3:::bucket/key", "arn:aws:s3:::bucket/uploads/key", "arn:aws:s3:::other/key"] {
+                let (a, r) = (action(a), resource(r));
+                assert_eq!(policies.validate_with_mode(&a, &r, EvaluationMode::AllowOnly), full_scan(&a, &r), "{a:?} {r}");
+            }
+        }
+
+        // The deny that wins under the default mode is ignored.
+        let object = resource("arn:aws:s3:::bucket/key");
+        assert!(!policies.validate(&action("s3:GetObject"), &object));
+        assert!(policies.validate_with_mode(&action("s3:GetObject"), &object, EvaluationMode::AllowOnly));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_remediation() {
+        let policies = collection(r#"[
+            {"statements": [
+                {"effect": "allow", "actions": ["s3:*"], "resources": ["arn:aws:s3:::bucket/*"]},
+                {"effect": "deny", "actions": ["s3:DeleteObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+            ]},
+            {"statements": [
+                {"effect": "allow", "actions": ["ec2:StartInstances"], "resources": ["arn:aws:ec2::123456789012:instance:i-1"]},
+                {"effect": "deny", "actions": ["iam:*"], "resources": ["arn:aws:iam::123456789012:*"]}
+            ]}
+        ]"#);
+
+        assert_eq!(
+            policies.remediation(&action("s3:GetObject"), &resource("arn:aws:s3:::bucket/key")),
+            Remediation::NotNeeded
+        );
+        assert_eq!(
+            policies.remediation(&action("s3:DeleteObject"), &resource("arn:aws:s3:::bucket/key")),
+            Remediation::EditDenies { denies: vec![(0, 1)], has_allow: true }
+        );
+        assert_eq!(
+            policies.remediation(&action("iam:CreateUser"), &resource("arn:aws:iam::123456789012:user/x")),
+            Remediation::EditDenies { denies: vec![(1, 1)], has_allow: false }
+        );
+        assert_eq!(
+            policies.remediation(&action("ec2:StartInstances"), &resource("arn:aws:ec2::123456789012:instance:i-2")),
+            Remediation::AddAllow { near_misses: vec![(1, 0)] }
+        );
+        assert_eq!(
+            policies.remediation(&action("sqs:SendMessage"), &resource("arn:aws:sqs::123456789012:queue")),
+            Remediation::AddAllow { near_misses: vec![] }
+        );
+    }
+}
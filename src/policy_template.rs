@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use serde_json::Value;
+use crate::engine::EngineTrait;
+use crate::error::IamError;
+use crate::variables::{self, UnknownVariable};
+use crate::Policy;
+
+/// A policy document with `${placeholder}` tokens, instantiated into a concrete [`Policy`] at
+/// runtime.
+///
+/// The template is kept as JSON, so placeholders may appear in segments that would not parse
+/// on their own, such as the region or account id. [`PolicyTemplate::instantiate`] substitutes
+/// every string in the document and only then parses it.
+///
+/// Template parameters are placeholders without a `:` in their name. Names containing `:`,
+/// such as `${aws:username}`, are policy variables resolved per request and are left in place.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use rust_iam::PolicyTemplate;
+/// use rust_iam::aws::AwsEngine;
+///
+/// let template = PolicyTemplate::<AwsEngine>::from_json(r#"{"statements": [
+///     {"effect": "allow", "actions": ["sqs:SendMessage"], "resources": ["arn:aws:sqs:${region}:${account}:jobs"]}
+/// ]}"#).unwrap();
+/// let params = HashMap::from([
+///     ("region".to_string(), "eu-west-1".to_string()),
+///     ("account".to_string(), "123456789012".to_string()),
+/// ]);
+/// let policy = template.instantiate(&params).unwrap();
+/// assert_eq!(policy.statements[0].resources[0].to_string(), "arn:aws:sqs:eu-west-1:123456789012:jobs");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyTemplate<Engine: EngineTrait> {
+    document: Value,
+    _engine: PhantomData<fn() -> Engine>,
+}
+
+impl<Engine: EngineTrait> PolicyTemplate<Engine> {
+    /// Wraps a policy document given as JSON.
+    pub fn new(document: Value) -> Self {
+        PolicyTemplate { document, _engine: PhantomData }
+    }
+
+    /// Parses a policy document from a JSON string without validating it as a policy.
+    ///
+    /// # Errors
+    /// Returns an error if `input` is not valid JSON.
+    pub fn from_json(input: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(input).map(Self::new)
+    }
+
+    /// Substitutes `params` into the template and parses the result.
+    ///
+    /// Substituted values are escaped like policy variable values, so a parameter can never
+    /// widen a pattern.
+    ///
+    /// # Parameters
+    /// - `params`: Values for the template parameters, keyed by name without `${}`.
+    ///
+    /// # Errors
+    /// - `IamError::Variable` if a template parameter has no value, or a value contains `:`.
+    /// - `IamError::Template` if the instantiated document is not a valid policy.
+    pub fn instantiate(&self, params: &HashMap<String, String>) -> Result<Policy<Engine>, IamError> {
+        let document = instantiate_value(&self.document, params)?;
+        serde_json::from_value(document).map_err(|e| IamError::Template(e.to_string()))
+    }
+}
+
+fn instantiate_value(value: &Value, params: &HashMap<String, String>) -> Result<Value, IamError> {
+    Ok(match value {
+        Value::String(text) => {
+            check_params(text, params)?;
+            Value::String(variables::substitute(text, params, UnknownVariable::Keep)?)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| instantiate_value(item, params)).collect::<Result<_, _>>()?),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, field)| Ok((key.clone(), instantiate_value(field, params)?)))
+                .collect::<Result<_, IamError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Fails on the first template parameter in `text` that has no value in `params`.
+fn check_params(text: &str, params: &HashMap<String, String>) -> Result<(), IamError> {
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            return Ok(());
+        };
+        let name = &after[..end];
+        if !name.contains(':') && !matches!(name, "*" | "?" | "$") && !params.contains_key(name) {
+            return Err(IamError::Variable { name: name.to_string(), reason: "no value supplied" });
+        }
+        rest = &after[end + 1..];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::AwsEngine;
+
+    const TEMPLATE: &str = r#"{"name": "${team}-queue-access", "statements": [
+        {"effect": "allow", "actions": ["sqs:SendMessage", "sqs:ReceiveMessage"],
+         "resources": ["arn:aws:sqs:${region}:${account}:${team}-*"]},
+        {"effect": "allow", "actions": ["s3:GetObject"],
+         "resources": ["arn:aws:s3:::${team}-home/${aws:username}/*"]}
+    ]}"#;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_instantiate_with_account_and_region() {
+        let template = PolicyTemplate::<AwsEngine>::from_json(TEMPLATE).unwrap();
+        let policy = template
+            .instantiate(&params(&[("account", "123456789012"), ("region", "us-east-1"), ("team", "billing")]))
+            .unwrap();
+        assert_eq!(policy.name.as_deref(), Some("billing-queue-access"));
+        assert_eq!(policy.statements[0].resources[0].to_string(), "arn:aws:sqs:us-east-1:123456789012:billing-*");
+        // Runtime policy variables survive instantiation.
+        assert_eq!(policy.statements[1].resources[0].to_string(), "arn:aws:s3:::billing-home/${aws:username}/*");
+
+        let other = template
+            .instantiate(&params(&[("account", "210987654321"), ("region", "eu-west-1"), ("team", "ops")]))
+            .unwrap();
+        assert_eq!(other.statements[0].resources[0].to_string(), "arn:aws:sqs:eu-west-1:210987654321:ops-*");
+    }
+
+    #[test]
+    fn test_instantiate_errors() {
+        let template = PolicyTemplate::<AwsEngine>::from_json(TEMPLATE).unwrap();
+        let missing = template.instantiate(&params(&[("account", "123456789012"), ("team", "billing")]));
+        assert_eq!(missing, Err(IamError::Variable { name: "region".to_string(), reason: "no value supplied" }));
+
+        let invalid = template.instantiate(&params(&[("account", "123456789012"), ("region", "zz"), ("team", "billing")]));
+        assert!(matches!(invalid, Err(IamError::Template(_))), "{invalid:?}");
+
+        // A parameter cannot widen the pattern it is substituted into.
+        let escaped = template.instantiate(&params(&[("account", "*"), ("region", "us-east-1"), ("team", "billing")])).unwrap();
+        assert_eq!(escaped.statements[0].resources[0].to_string(), "arn:aws:sqs:us-east-1:\\*:billing-*");
+    }
+}
@@ -0,0 +1,104 @@
+use crate::MaybeEffect;
+
+/// Describes how statement and policy effects are combined into a single decision.
+///
+/// [`Policy::matches`](crate::Policy::matches) and
+/// [`PolicyCollection::validate`](crate::PolicyCollection::validate) use
+/// `PrecedenceConfig::default()`, which encodes AWS semantics: an explicit deny always wins,
+/// an explicit allow beats an implicit result, and a request nothing speaks to is denied.
+/// The `_with` variants of those methods accept a different configuration.
+///
+/// # Fields
+/// - `deny_wins`: If `true`, any explicit deny overrides every allow. If `false`, any explicit
+///   allow overrides every deny.
+/// - `default_allow`: If `true`, a request no statement speaks to is allowed instead of
+///   implicitly denied.
+///
+/// # Examples
+/// ```
+/// use rust_iam::{MaybeEffect, PrecedenceConfig};
+///
+/// let effects = [MaybeEffect::Allow, MaybeEffect::Deny];
+/// assert_eq!(PrecedenceConfig::default().combine(effects), MaybeEffect::Deny);
+///
+/// let allow_wins = PrecedenceConfig { deny_wins: false, ..Default::default() };
+/// assert_eq!(allow_wins.combine(effects), MaybeEffect::Allow);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrecedenceConfig {
+    pub deny_wins: bool,
+    pub default_allow: bool,
+}
+
+impl Default for PrecedenceConfig {
+    fn default() -> Self {
+        PrecedenceConfig {
+            deny_wins: true,
+            default_allow: false,
+        }
+    }
+}
+
+impl PrecedenceConfig {
+    /// Combines several effects into one, stopping as soon as the winning effect is seen.
+    ///
+    /// # Returns
+    /// - The winning explicit effect if one is present.
+    /// - The other explicit effect if only that one is present.
+    /// - `MaybeEffect::NotSpecified` if no effect is explicit.
+    pub fn combine<I: IntoIterator<Item = MaybeEffect>>(&self, effects: I) -> MaybeEffect {
+        let (winner, loser) = if self.deny_wins {
+            (MaybeEffect::Deny, MaybeEffect::Allow)
+        } else {
+            (MaybeEffect::Allow, MaybeEffect::Deny)
+        };
+        let mut seen_loser = false;
+        for effect in effects {
+            if effect == winner {
+                return winner;
+            }
+            seen_loser |= effect == loser;
+        }
+        if seen_loser {
+            loser
+        } else {
+            MaybeEffect::NotSpecified
+        }
+    }
+
+    /// Turns a combined effect into a final allow/deny decision.
+    ///
+    /// # Returns
+    /// - `true` for `MaybeEffect::Allow`, or for `MaybeEffect::NotSpecified` when `default_allow` is set.
+    /// - `false` otherwise.
+    pub fn is_allowed(&self, effect: MaybeEffect) -> bool {
+        match effect {
+            MaybeEffect::Allow => true,
+            MaybeEffect::Deny => false,
+            MaybeEffect::NotSpecified => self.default_allow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_deny_wins() {
+        let config = PrecedenceConfig::default();
+        assert_eq!(config.combine([MaybeEffect::Allow, MaybeEffect::Deny, MaybeEffect::Allow]), MaybeEffect::Deny);
+        assert_eq!(config.combine([MaybeEffect::NotSpecified, MaybeEffect::Allow]), MaybeEffect::Allow);
+        assert_eq!(config.combine([]), MaybeEffect::NotSpecified);
+        assert!(!config.is_allowed(MaybeEffect::NotSpecified));
+    }
+
+    #[test]
+    fn test_allow_wins() {
+        let config = PrecedenceConfig { deny_wins: false, default_allow: true };
+        assert_eq!(config.combine([MaybeEffect::Deny, MaybeEffect::Allow]), MaybeEffect::Allow);
+        assert_eq!(config.combine([MaybeEffect::Deny, MaybeEffect::NotSpecified]), MaybeEffect::Deny);
+        assert!(config.is_allowed(MaybeEffect::NotSpecified));
+        assert!(!config.is_allowed(MaybeEffect::Deny));
+    }
+}
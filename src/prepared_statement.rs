@@ -0,0 +1,296 @@
+use std::any::Any;
+use wildcard::Wildcard;
+use crate::aws::{ResourceIdString, WildString};
+use crate::engine::EngineTrait;
+use crate::error::{IamError, MatchErrorMode};
+use crate::resource_ref::FastGlob;
+use crate::traits::MatchesTrait;
+use crate::{Effect, MaybeEffect, ResourceAbstract, Statement};
+
+/// A wildcard pattern compiled once, using the same fast paths as segment matching.
+#[derive(Debug, Clone)]
+enum CompiledGlob {
+    Fast(FastGlob<String>),
+    Automaton(Wildcard<'static>),
+    Invalid,
+}
+
+impl CompiledGlob {
+    fn new(pattern: &str) -> Self {
+        if let Some(fast) = FastGlob::parse(pattern) {
+            return CompiledGlob::Fast(fast.into_owned());
+        }
+        Wildcard::from_owned(pattern.as_bytes().to_vec()).map_or(CompiledGlob::Invalid, CompiledGlob::Automaton)
+    }
+
+    fn is_match(&self, value: &str) -> Result<bool, &'static str> {
+        match self {
+            CompiledGlob::Fast(fast) => Ok(fast.is_match(value)),
+            CompiledGlob::Automaton(pattern) => Ok(pattern.is_match(value.as_bytes())),
+            CompiledGlob::Invalid => Err("Failed to compile wildcard pattern"),
+        }
+    }
+}
+
+/// Returns the text a segment is wildcard-matched on, for the segment types whose matching is
+/// a plain glob over that text. Other types keep their own [`MatchesTrait`] implementation.
+fn glob_text<T: 'static>(value: &T) -> Option<&str> {
+    let value = value as &dyn Any;
+    value
+        .downcast_ref::<WildString>()
+        .map(|s| s.0.as_str())
+        .or_else(|| value.downcast_ref::<ResourceIdString>().map(ResourceIdString::normalized))
+}
+
+fn compile<T: 'static>(segment: Option<&T>) -> Option<CompiledGlob> {
+    segment.and_then(glob_text).map(CompiledGlob::new)
+}
+
+/// Matches one segment, using the compiled pattern when both sides have glob text.
+fn segment_matches<T: MatchesTrait<bool> + 'static>(
+    compiled: &Option<CompiledGlob>,
+    pattern: Option<&T>,
+    value: Option<&T>,
+) -> Result<bool, &'static str> {
+    let (pattern, value) = match (pattern, value) {
+        (Some(pattern), Some(value)) => (pattern, value),
+        (Some(_), None) => return Ok(false),
+        (None, _) => return Ok(true),
+    };
+    match (compiled, glob_text(value)) {
+        (Some(compiled), Some(text)) => compiled.is_match(text),
+        _ => pattern.matches(value),
+    }
+}
+
+/// A resource pattern with its glob segments compiled.
+#[derive(Debug, Clone)]
+struct PreparedResource<Engine: EngineTrait> {
+    resource: ResourceAbstract<Engine>,
+    segments: [Option<CompiledGlob>; 6],
+    qualifier: Option<CompiledGlob>,
+    trailing: Vec<CompiledGlob>,
+}
+
+impl<Engine: EngineTrait> PreparedResource<Engine> {
+    fn new(resource: &ResourceAbstract<Engine>) -> Self {
+        PreparedResource {
+            segments: [
+                compile(resource.partition.as_ref()),
+                compile(resource.service.as_ref()),
+                compile(resource.region.as_ref()),
+                compile(resource.account_id.as_ref()),
+                compile(resource.resource_type.as_ref()),
+                compile(resource.resource_id.as_ref()),
+            ],
+            qualifier: resource.qualifier.as_deref().map(CompiledGlob::new),
+            trailing: resource.trailing.iter().map(|s| CompiledGlob::new(s)).collect(),
+            resource: resource.clone(),
+        }
+    }
+
+    /// Mirrors [`ResourceAbstract`]'s `matches` segment by segment.
+    fn matches(&self, other: &ResourceAbstract<Engine>) -> Result<bool, &'static str> {
+        let r = &self.resource;
+        let [partition, service, region, account_id, resource_type, resource_id] = &self.segments;
+        let segments_match = segment_matches(partition, r.partition.as_ref(), other.partition.as_ref())?
+            && segment_matches(service, r.service.as_ref(), other.service.as_ref())?
+            && segment_matches(region, r.region.as_ref(), other.region.as_ref())?
+            && segment_matches(account_id, r.account_id.as_ref(), other.account_id.as_ref())?
+            && segment_matches(resource_type, r.resource_type.as_ref(), other.resource_type.as_ref())?
+            && segment_matches(resource_id, r.resource_id.as_ref(), other.resource_id.as_ref())?;
+        if !segments_match {
+            return Ok(false);
+        }
+        match (self.qualifier.as_ref(), other.qualifier.as_ref()) {
+            (Some(l), Some(r)) if !l.is_match(r)? => return Ok(false),
+            (Some(_), None) => return Ok(false),
+            _ => {}
+        }
+        if !self.trailing.is_empty() && !other.trailing.is_empty() {
+            if self.trailing.len() != other.trailing.len() {
+                return Ok(false);
+            }
+            for (l, r) in self.trailing.iter().zip(other.trailing.iter()) {
+                if !l.is_match(r)? {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// A [`Statement`] whose wildcard patterns are compiled once for repeated evaluation.
+///
+/// Created by [`Statement::prepare`]. Segments of type [`WildString`] or [`ResourceIdString`]
+/// are compiled up front; other segment types, such as regions, are matched with their own
+/// [`MatchesTrait`] implementation as usual. Results are identical to the unprepared statement.
+///
+/// # Examples
+/// ```
+/// use rust_iam::{MaybeEffect, ResourceAbstract, Statement};
+/// use rust_iam::aws::{AwsEngine, WildString};
+///
+/// let statement: Statement<AwsEngine> = serde_json::from_str(
+///     r#"{"effect": "allow", "actions": ["s3:Get*"], "resources": ["arn:aws:s3:::bucket/*.csv"]}"#,
+/// ).unwrap();
+/// let prepared = statement.prepare();
+/// let object: ResourceAbstract<AwsEngine> = "arn:aws:s3:::bucket/q1.csv".parse().unwrap();
+/// let action = WildString("s3:GetObject".to_string());
+/// assert_eq!(prepared.matches(&action, &object), MaybeEffect::Allow);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PreparedStatement<Engine: EngineTrait> {
+    statement: Statement<Engine>,
+    actions: Vec<Option<CompiledGlob>>,
+    resources: Vec<PreparedResource<Engine>>,
+    except_resources: Vec<PreparedResource<Engine>>,
+}
+
+impl<Engine: EngineTrait> PreparedStatement<Engine> {
+    pub(crate) fn new(statement: &Statement<Engine>) -> Self {
+        PreparedStatement {
+            // Case-insensitive engines match actions through `EngineTrait::action_matches`
+            actions: statement
+                .actions
+                .iter()
+                .map(|a| if Engine::CASE_INSENSITIVE_ACTIONS { None } else { compile(Some(a)) })
+                .collect(),
+            resources: statement.resources.iter().map(PreparedResource::new).collect(),
+            except_resources: statement.except_resources.iter().map(PreparedResource::new).collect(),
+            statement: statement.clone(),
+        }
+    }
+
+    /// Returns the statement this was prepared from.
+    pub fn statement(&self) -> &Statement<Engine> {
+        &self.statement
+    }
+
+    /// Same as [`Statement::matches`], without recompiling patterns.
+    pub fn matches(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> MaybeEffect {
+        self.matches_with_errors(action, resource, MatchErrorMode::FailClosed)
+            .unwrap_or(MaybeEffect::NotSpecified)
+    }
+
+    /// Same as [`Statement::matches_with_errors`], without recompiling patterns.
+    pub fn matches_with_errors(
+        &self,
+        action: &Engine::Action,
+        resource: &ResourceAbstract<Engine>,
+        mode: MatchErrorMode,
+    ) -> Result<MaybeEffect, IamError> {
+        match self.try_matches(action, resource) {
+            Ok(effect) => Ok(effect),
+            Err(err) => match (mode, &self.statement.effect) {
+                (MatchErrorMode::Propagate, _) => Err(err),
+                (MatchErrorMode::FailClosed, Effect::Deny) => Ok(MaybeEffect::Deny),
+                (MatchErrorMode::FailOpen, Effect::Allow) => Ok(MaybeEffect::Allow),
+                _ => Ok(MaybeEffect::NotSpecified),
+            },
+        }
+    }
+
+    /// Same as [`Statement::try_matches`], without recompiling patterns.
+    pub fn try_matches(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Result<MaybeEffect, IamError> {
+        for r in self.except_resources.iter() {
+            if r.matches(resource)? {
+                return Ok(MaybeEffect::NotSpecified);
+            }
+        }
+        let mut is_allow = false;
+        for r in self.resources.iter() {
+            if r.matches(resource)? {
+                for (compiled, a) in self.actions.iter().zip(self.statement.actions.iter()) {
+                    let matched = match (compiled, glob_text(action)) {
+                        (Some(compiled), Some(text)) => compiled.is_match(text)?,
+                        _ => Engine::action_matches(a, action)?,
+                    };
+                    if matched {
+                        if self.statement.effect == Effect::Deny {
+                            return Ok(MaybeEffect::Deny);
+                        }
+                        is_allow = true;
+                    }
+                }
+            }
+        }
+        if is_allow {
+            Ok(MaybeEffect::Allow)
+        } else {
+            Ok(MaybeEffect::NotSpecified)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::{AwsCaseInsensitiveEngine, AwsEngine, AwsServiceEnumEngine};
+    use std::str::FromStr;
+
+    const STATEMENTS: &[&str] = &[
+        r#"{"effect": "allow", "actions": ["s3:Get*", "s3:*Object", "s3:Get?bject*"], "resources": ["arn:aws:s3:::bucket/*.csv", "arn:aws:s3:::*-logs-*"]}"#,
+        r#"{"effect": "deny", "actions": ["*"], "resources": ["arn:aws:s3:::bucket/secret/*"]}"#,
+        r#"{"effect": "allow", "actions": ["ec2:*"], "resources": ["arn:aws:ec2:eu-*:12345678901?:instance:*"],
+            "except_resources": ["arn:aws:ec2:eu-west-1:*:instance:i-0"]}"#,
+        r#"{"effect": "allow", "actions": ["lambda:InvokeFunction"], "resources": ["arn:aws:lambda:*:*:function:my-fn:prod*"]}"#,
+        r#"{"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/a%20b*"]}"#,
+        r#"{"effect": "allow", "actions": ["lambda:*"], "resources": ["arn:aws:lambda:*:*:function:my-fn:*:ext*"]}"#,
+        r#"{"effect": "deny", "actions": ["s3:Get\\"], "resources": ["*"]}"#,
+    ];
+
+    const REQUESTS: &[(&str, &str)] = &[
+        ("s3:GetObject", "arn:aws:s3:::bucket/q1.csv"),
+        ("s3:PutObject", "arn:aws:s3:::app-logs-2024"),
+        ("s3:GetObject", "arn:aws:s3:::bucket/secret/keys.csv"),
+        ("s3:GetObject", "arn:aws:s3:::bucket/a b.txt"),
+        ("ec2:StartInstances", "arn:aws:ec2:eu-central-1:123456789012:instance:i-1"),
+        ("ec2:StartInstances", "arn:aws:ec2:eu-west-1:123456789012:instance:i-0"),
+        ("ec2:StartInstances", "arn:aws:ec2:us-east-1:123456789012:instance:i-1"),
+        ("lambda:InvokeFunction", "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod-2"),
+        ("lambda:InvokeFunction", "arn:aws:lambda:us-west-2:123456789012:function:my-fn:dev"),
+        ("lambda:InvokeFunction", "arn:aws:lambda:us-west-2:123456789012:function:my-fn"),
+        ("lambda:InvokeFunction", "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod-2:extra"),
+    ];
+
+    fn assert_equivalent<Engine: EngineTrait>()
+    where
+        Engine::Action: FromStr,
+    {
+        for json in STATEMENTS {
+            let statement: Statement<Engine> = serde_json::from_str(json).unwrap();
+            let prepared = statement.prepare();
+            for (action, resource) in REQUESTS {
+                let Ok(action) = Engine::Action::from_str(action) else { continue };
+                let resource = ResourceAbstract::<Engine>::from_str(resource).unwrap();
+                for mode in [MatchErrorMode::FailClosed, MatchErrorMode::FailOpen, MatchErrorMode::Propagate] {
+                    assert_eq!(
+                        prepared.matches_with_errors(&action, &resource, mode),
+                        statement.matches_with_errors(&action, &resource, mode),
+                        "{json} {action:?} {resource}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prepared_statement_agrees_with_statement() {
+        assert_equivalent::<AwsEngine>();
+        assert_equivalent::<AwsServiceEnumEngine>();
+        assert_equivalent::<AwsCaseInsensitiveEngine>();
+    }
+
+    #[test]
+    fn test_invalid_patterns_still_error() {
+        let statement: Statement<AwsEngine> = serde_json::from_str(STATEMENTS[6]).unwrap();
+        let prepared = statement.prepare();
+        let action = WildString("s3:GetObject".to_string());
+        let resource = ResourceAbstract::from_str("arn:aws:s3:::bucket/key").unwrap();
+        assert!(prepared.try_matches(&action, &resource).is_err());
+        assert_eq!(prepared.matches(&action, &resource), MaybeEffect::Deny);
+        assert_eq!(prepared.statement(), &statement);
+    }
+}
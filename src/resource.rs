@@ -2,7 +2,13 @@ use std::fmt::Debug;
 use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use crate::engine::EngineTrait;
+use crate::error::IamError;
+use crate::statement::MatchKind;
+use crate::resource_ref::{Glob, GlobList};
+use crate::variables::{self, UnknownVariable};
+use std::collections::HashMap;
 use crate::traits::MatchesTrait;
+use wildcard::Wildcard;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ResourceAbstract<Engine: EngineTrait> {
@@ -18,38 +24,54 @@ pub struct ResourceAbstract<Engine: EngineTrait> {
     pub resource_type: Option<Engine::ResourceType>,
     // The resource identifier. The name of the resource, the ID of the resource, or a resource path. Some identifiers include a parent resource sub-resource-type/parent-resource/sub-resource) or a qualifier such as a version (resource-type:resource-name:qualifier)
     pub resource_id: Option<Engine::ResourceID>,
+    // An optional qualifier following the resource id, such as a Lambda version or alias (function:my-function:PROD). A resource without a qualifier matches any qualifier, while a qualified one does not match an unqualified request.
+    pub qualifier: Option<String>,
+    // Any segments after the qualifier, kept verbatim (including empty ones) so ARNs with more segments than this crate knows about round-trip intact. Only compared when both sides have them.
+    pub trailing: Vec<String>,
 }
-use serde::ser::{self, Serializer};
+use serde::ser::Serializer;
 use std::fmt;
+impl<Engine: EngineTrait> fmt::Display for ResourceAbstract<Engine> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segments = self.arn_segments();
+        // A resource with nothing set matches everything, which AWS writes as `*`
+        if segments.is_empty() {
+            return f.write_str("*");
+        }
+        write!(f, "arn:{}", segments.join(":"))
+    }
+}
+
+/// Compares a resource with an ARN string by rendering the resource as an ARN.
+///
+/// This is exact string equality: `"arn:aws:s3:::*"` only equals a resource whose own ARN
+/// is `arn:aws:s3:::*`. Use [`MatchesTrait::matches`] for wildcard matching.
+impl<Engine: EngineTrait> PartialEq<str> for ResourceAbstract<Engine> {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl<Engine: EngineTrait> PartialEq<&str> for ResourceAbstract<Engine> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
 impl<Engine: EngineTrait> Serialize for ResourceAbstract<Engine> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        fn serialize_field<T: ToString>(field: &Option<T>) -> String {
-            match field {
-                Some(value) => value.to_string(),
-                None => "".to_string(),
-            }
-        }
-
-        // Construct the colon-separated string
-        let serialized_string = format!(
-            "arn:{}:{}:{}:{}:{}:{}",
-            serialize_field(&self.partition),
-            serialize_field(&self.service),
-            serialize_field(&self.region),
-            serialize_field(&self.account_id),
-            serialize_field(&self.resource_type),
-            serialize_field(&self.resource_id)
-        );
-
-        // Serialize the resulting string
-        serializer.serialize_str(&serialized_string)
+        serializer.collect_str(self)
     }
 }
 
-use serde::de::{self, Deserializer, Error, MapAccess, Visitor};
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+
+/// Keys accepted by the object form of a resource.
+const MAP_FIELDS: &[&str] = &["partition", "service", "region", "accountId", "resourceType", "resourceId", "qualifier"];
+
 impl<'de, Engine: EngineTrait> Deserialize<'de> for ResourceAbstract<Engine> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -61,7 +83,7 @@ impl<'de, Engine: EngineTrait> Deserialize<'de> for ResourceAbstract<Engine> {
             type Value = ResourceAbstract<Engine>;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a string that can be parsed into a ResourceAbstract")
+                formatter.write_str("an ARN string or a map of named ARN segments")
             }
 
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
@@ -70,37 +92,151 @@ impl<'de, Engine: EngineTrait> Deserialize<'de> for ResourceAbstract<Engine> {
             {
                 ResourceAbstract::<Engine>::from_str(value).map_err(de::Error::custom)
             }
+
+            /// Accepts the object form, e.g. `{"service": "s3", "resourceType": "bucket"}`.
+            /// Segments that are left out are treated as missing, like empty ARN segments.
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                fn parse<T: FromStr<Err = &'static str>, E: de::Error>(
+                    slot: &mut Option<T>,
+                    field: &'static str,
+                    value: String,
+                ) -> Result<(), E> {
+                    if slot.is_some() {
+                        return Err(E::duplicate_field(field));
+                    }
+                    *slot = Some(T::from_str(&value).map_err(E::custom)?);
+                    Ok(())
+                }
+
+                let mut resource = ResourceAbstract::<Engine> {
+                    partition: None,
+                    service: None,
+                    region: None,
+                    account_id: None,
+                    resource_type: None,
+                    resource_id: None,
+                    qualifier: None,
+                    trailing: Vec::new(),
+                };
+
+                while let Some(key) = map.next_key::<String>()? {
+                    let value: String = map.next_value()?;
+                    match key.as_str() {
+                        "partition" => parse(&mut resource.partition, "partition", value)?,
+                        "service" => parse(&mut resource.service, "service", value)?,
+                        "region" => parse(&mut resource.region, "region", value)?,
+                        "accountId" => parse(&mut resource.account_id, "accountId", value)?,
+                        "resourceType" => parse(&mut resource.resource_type, "resourceType", value)?,
+                        "resourceId" => parse(&mut resource.resource_id, "resourceId", value)?,
+                        "qualifier" => {
+                            if resource.qualifier.is_some() {
+                                return Err(de::Error::duplicate_field("qualifier"));
+                            }
+                            resource.qualifier = Some(value);
+                        }
+                        _ => return Err(de::Error::unknown_field(&key, MAP_FIELDS)),
+                    }
+                }
+
+                Ok(resource)
+            }
         }
 
-        deserializer.deserialize_str(ResourceAbstractVisitor(std::marker::PhantomData))
+        // Binary formats are not self-describing and always carry the string form
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ResourceAbstractVisitor(std::marker::PhantomData))
+        } else {
+            deserializer.deserialize_str(ResourceAbstractVisitor(std::marker::PhantomData))
+        }
     }
 }
 
 impl<Engine: EngineTrait> FromStr for ResourceAbstract<Engine>
 {
-    type Err = String;
+    type Err = IamError;
 
+    /// Parses an ARN, reporting the byte offset and segment index of the first failure as
+    /// [`IamError::MalformedArn`]. Segment 0 is the `arn` prefix, segment 1 the partition, and so on.
+    ///
+    /// The AWS shorthand `*` parses to a resource with every segment unset, which matches any
+    /// resource.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "*" {
+            return Ok(ResourceAbstract::any());
+        }
         if !s.starts_with("arn:") {
-            return Err("Invalid resource format: Resource name should start with 'arn:'".to_string());
+            return Err(IamError::MalformedArn {
+                offset: 0,
+                segment: 0,
+                reason: "Invalid resource format: Resource name should start with 'arn:'",
+            });
         }
 
-        let mut split = s.split(':');
+        Self::parse_segments(s["arn:".len()..].split(':'))
+    }
+}
+
+impl<Engine: EngineTrait> ResourceAbstract<Engine> {
+    /// Builds a resource from ARN segments that were already split, e.g. by a request parser.
+    ///
+    /// `segments` are the pieces after the `arn:` prefix, in order. They are parsed exactly as
+    /// [`FromStr`] parses the colon-separated pieces of an ARN, but are never split again, so a
+    /// segment may contain `:`. This lets the caller decide where a resource id containing
+    /// colons ends. Empty segments are treated as missing.
+    ///
+    /// # Errors
+    /// Returns `IamError::MalformedArn` if a segment fails to parse. Offsets refer to the
+    /// segments joined with `:` behind an `arn:` prefix.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::ResourceAbstract;
+    /// use rust_iam::aws::AwsEngine;
+    ///
+    /// let key = ResourceAbstract::<AwsEngine>::from_segments(&["aws", "s3", "", "", "bucket/a:b"]).unwrap();
+    /// assert_eq!(key.resource_type.unwrap().0, "bucket/a:b");
+    /// ```
+    pub fn from_segments(segments: &[&str]) -> Result<Self, IamError> {
+        Self::parse_segments(segments.iter().copied())
+    }
 
-        // Skip the "arn" prefix
-        split.next();
+    fn parse_segments<'a>(segments: impl Iterator<Item = &'a str>) -> Result<Self, IamError> {
+        // Track where each segment starts in the joined ARN so errors can point at it. Empty
+        // segments (e.g. the region of an S3 ARN) are treated as missing.
+        let mut offset = "arn:".len();
+        let mut split = segments
+            .map(|segment| {
+                let start = offset;
+                offset += segment.len() + 1;
+                (start, Some(segment).filter(|s| !s.is_empty()))
+            })
+            .enumerate()
+            .map(|(index, segment)| (index + 1, segment));
 
-        fn flip<T, E>(input: Option<Result<T, E>>) -> Result<Option<T>, E> {
-            input.map_or(Ok(None), |res| res.map(Some))
+        fn parse<T: FromStr<Err = &'static str>>(
+            input: Option<(usize, (usize, Option<&str>))>,
+        ) -> Result<Option<T>, IamError> {
+            match input {
+                Some((segment, (offset, Some(value)))) => T::from_str(value)
+                    .map(Some)
+                    .map_err(|reason| IamError::MalformedArn { offset, segment, reason }),
+                _ => Ok(None),
+            }
         }
 
-        // Parse the components with proper error handling
-        let partition = flip(split.next().map(|f| Engine::Partition::from_str(f)))?;
-        let service = flip(split.next().map(|f| Engine::Service::from_str(f)))?;
-        let region = flip(split.next().map(|f| Engine::Region::from_str(f)))?;
-        let account_id = flip(split.next().map(|f| Engine::AccountID::from_str(f)))?;
-        let resource_type = flip(split.next().map(|f| Engine::ResourceType::from_str(f)))?;
-        let resource_id = flip(split.next().map(|f| Engine::ResourceID::from_str(f)))?;
+        // Parse the components with proper error handling, only consuming the segments the engine uses
+        let mut typed = split.by_ref().take(Engine::ARN_SEGMENTS);
+        let partition = parse::<Engine::Partition>(typed.next())?;
+        let service = parse::<Engine::Service>(typed.next())?;
+        let region = parse::<Engine::Region>(typed.next())?;
+        let account_id = parse::<Engine::AccountID>(typed.next())?;
+        let resource_type = parse::<Engine::ResourceType>(typed.next())?;
+        let resource_id = parse::<Engine::ResourceID>(typed.next())?;
+        let qualifier = split.next().and_then(|(_, (_, segment))| segment).map(str::to_string);
+        let trailing = split.map(|(_, (_, segment))| segment.unwrap_or_default().to_string()).collect();
 
         let resource = ResourceAbstract {
             partition,
@@ -109,6 +245,8 @@ impl<Engine: EngineTrait> FromStr for ResourceAbstract<Engine>
             account_id,
             resource_type,
             resource_id,
+            qualifier,
+            trailing,
         };
 
         Ok(resource)
@@ -116,58 +254,984 @@ impl<Engine: EngineTrait> FromStr for ResourceAbstract<Engine>
 }
 
 impl<Engine: EngineTrait> MatchesTrait<bool> for ResourceAbstract<Engine> {
+    /// Matches `other` against this resource segment by segment.
+    ///
+    /// A segment this resource leaves unset matches anything, while a set segment does not
+    /// match a request that leaves it unset (see the `Option` impl of [`MatchesTrait`]).
     fn matches(&self, other: &ResourceAbstract<Engine>) -> Result<bool, &'static str> {
-        match (self.partition.as_ref(), other.partition.as_ref()) {
-            (Some(l), Some(r)) => {
-                if !l.matches(r)? {
-                    return Ok(false);
-                }
-            }
-            _ => {}
+        self.matches_with_options(other, MatchOptions::default())
+    }
+}
+
+impl<Engine: EngineTrait> ResourceAbstract<Engine> {
+    /// Matches `other` against this resource, skipping the segments `options` relaxes.
+    ///
+    /// With `MatchOptions::default()` this is exactly [`MatchesTrait::matches`]. An ignored
+    /// segment matches whatever either side sets, as if this resource left it unset.
+    ///
+    /// # Parameters
+    /// - `other`: The request resource to test.
+    /// - `options`: The segments to leave out of the comparison.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if `other` matches this resource on every segment that is compared.
+    /// - `Err` if a pattern could not be evaluated.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::{MatchOptions, ResourceAbstract};
+    /// use rust_iam::aws::AwsEngine;
+    /// use rust_iam::traits::MatchesTrait;
+    ///
+    /// let role: ResourceAbstract<AwsEngine> = "arn:aws:iam::111111111111:role/deployer".parse().unwrap();
+    /// let other: ResourceAbstract<AwsEngine> = "arn:aws:iam::222222222222:role/deployer".parse().unwrap();
+    /// assert_eq!(role.matches(&other), Ok(false));
+    /// let options = MatchOptions { ignore_account: true, ..Default::default() };
+    /// assert_eq!(role.matches_with_options(&other, options), Ok(true));
+    /// ```
+    pub fn matches_with_options(&self, other: &ResourceAbstract<Engine>, options: MatchOptions) -> Result<bool, &'static str> {
+        let segments_match = self.partition.matches(&other.partition)?
+            && self.service.matches(&other.service)?
+            && (options.ignore_region || self.region.matches(&other.region)?)
+            && (options.ignore_account || self.account_id.matches(&other.account_id)?)
+            && self.resource_type.matches(&other.resource_type)?
+            && self.resource_id.matches(&other.resource_id)?;
+        if !segments_match {
+            return Ok(false);
+        }
+        Ok(self.qualifier.as_deref().map(Glob).matches(&other.qualifier.as_deref().map(Glob))?
+            && GlobList(&self.trailing).matches(&GlobList(&other.trailing))?)
+    }
+}
+
+/// The outcome of comparing one ARN segment, as reported by [`ResourceAbstract::match_detail`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentMatch {
+    /// Both sides set the segment and the pattern matched.
+    Matched,
+
+    /// The pattern left the segment unset, so it was not compared.
+    Skipped,
+
+    /// The pattern set the segment and the request either left it unset or did not match.
+    Mismatched,
+
+    /// The pattern could not be evaluated.
+    Error(&'static str),
+}
+
+/// A per-segment breakdown of [`ResourceAbstract`] matching.
+///
+/// # Fields
+/// - `segments`: The partition, service, region, account id, resource type and resource id
+///   outcomes, in ARN order.
+/// - `qualifier`: The qualifier outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentMatchReport {
+    pub segments: [SegmentMatch; 6],
+    pub qualifier: SegmentMatch,
+}
+
+impl SegmentMatchReport {
+    /// Returns the index of the first segment that mismatched or failed, if any.
+    pub fn first_failure(&self) -> Option<usize> {
+        self.segments
+            .iter()
+            .position(|segment| matches!(segment, SegmentMatch::Mismatched | SegmentMatch::Error(_)))
+    }
+}
+
+impl SegmentMatch {
+    /// Compares one optional segment through the `Option` impl of [`MatchesTrait`].
+    fn of<T: MatchesTrait<bool>>(pattern: &Option<T>, value: &Option<T>) -> Self {
+        if pattern.is_none() {
+            return SegmentMatch::Skipped;
+        }
+        match pattern.matches(value) {
+            Ok(true) => SegmentMatch::Matched,
+            Ok(false) => SegmentMatch::Mismatched,
+            Err(reason) => SegmentMatch::Error(reason),
+        }
+    }
+}
+
+/// Selects how a policy resource is compared against a request resource.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceMatchMode {
+    /// Each ARN segment is matched on its own, so a wildcard never crosses a `:` boundary.
+    /// A request that leaves out a segment or qualifier the policy resource sets does not
+    /// match.
+    #[default]
+    PerSegment,
+
+    /// The full ARN strings are compared, treating the policy ARN as a single wildcard
+    /// pattern. A `*` may then span several segments, e.g. `arn:aws:s3:::*.csv` matches
+    /// `arn:aws:s3:::bucket/reports:2024.csv`. Trailing empty segments are ignored on both sides.
+    WholeArn,
+
+    /// Like `PerSegment`, except that an under-specified request (one that leaves out a
+    /// segment or qualifier the policy resource sets) is an error instead of `Ok(false)`, so
+    /// gateways can reject it outright.
+    Strict,
+}
+
+/// Segments to leave out when matching with [`ResourceAbstract::matches_with_options`].
+///
+/// The default compares every segment, like [`MatchesTrait::matches`].
+///
+/// # Fields
+/// - `ignore_account`: Match regardless of the account that owns the resource, e.g. for
+///   cross-account tooling.
+/// - `ignore_region`: Match regardless of the region the resource lives in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MatchOptions {
+    pub ignore_account: bool,
+    pub ignore_region: bool,
+}
+
+/// One of the six positional ARN segments, in the order they appear after `arn:`.
+///
+/// # Examples
+/// ```
+/// use rust_iam::{ArnSegment, ResourceAbstract};
+/// use rust_iam::aws::AwsEngine;
+///
+/// let role: ResourceAbstract<AwsEngine> = "arn:aws:iam::123456789012:role/deployer".parse().unwrap();
+/// assert_eq!(role.segment_str(ArnSegment::AccountId).as_deref(), Some("123456789012"));
+/// assert_eq!(role.segment_str(ArnSegment::Region), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArnSegment {
+    Partition,
+    Service,
+    Region,
+    AccountId,
+    ResourceType,
+    ResourceId,
+}
+
+impl ArnSegment {
+    /// Every segment, in ARN order.
+    pub const ALL: [ArnSegment; 6] = [
+        ArnSegment::Partition,
+        ArnSegment::Service,
+        ArnSegment::Region,
+        ArnSegment::AccountId,
+        ArnSegment::ResourceType,
+        ArnSegment::ResourceId,
+    ];
+
+    /// Returns the zero-based position of the segment after the `arn:` prefix.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl<Engine: EngineTrait> ResourceAbstract<Engine> {
+    /// Returns the string form of one segment, or `None` if it is unset.
+    pub fn segment_str(&self, segment: ArnSegment) -> Option<String> {
+        match segment {
+            ArnSegment::Partition => self.partition.as_ref().map(ToString::to_string),
+            ArnSegment::Service => self.service.as_ref().map(ToString::to_string),
+            ArnSegment::Region => self.region.as_ref().map(ToString::to_string),
+            ArnSegment::AccountId => self.account_id.as_ref().map(ToString::to_string),
+            ArnSegment::ResourceType => self.resource_type.as_ref().map(ToString::to_string),
+            ArnSegment::ResourceId => self.resource_id.as_ref().map(ToString::to_string),
+        }
+    }
+
+    /// Iterates over all six segments in ARN order, with the string form of each one that is set.
+    pub fn segments(&self) -> impl Iterator<Item = (ArnSegment, Option<String>)> + '_ {
+        ArnSegment::ALL.into_iter().map(move |segment| (segment, self.segment_str(segment)))
+    }
+
+    /// Returns the string form of the six positional segments, `None` where unset.
+    pub(crate) fn segment_strings(&self) -> [Option<String>; 6] {
+        ArnSegment::ALL.map(|segment| self.segment_str(segment))
+    }
+
+    /// Returns the resource with every segment unset, written `*`, which matches any resource.
+    pub fn any() -> Self {
+        ResourceAbstract {
+            partition: None,
+            service: None,
+            region: None,
+            account_id: None,
+            resource_type: None,
+            resource_id: None,
+            qualifier: None,
+            trailing: Vec::new(),
+        }
+    }
+
+    /// Parses an ARN from untidy input such as logs, then applies the strict [`FromStr`] parser.
+    ///
+    /// Surrounding whitespace is trimmed, the `arn` prefix is matched case-insensitively, and
+    /// every `delimiter` is read as `:`. Because the delimiter is replaced throughout, it must not
+    /// occur inside a segment; pass `':'` to only relax the prefix.
+    ///
+    /// # Parameters
+    /// - `input`: The possibly mangled ARN.
+    /// - `delimiter`: The character separating segments in `input`.
+    ///
+    /// # Errors
+    /// Returns `IamError::MalformedArn` if the normalized input is not a valid ARN.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::ResourceAbstract;
+    /// use rust_iam::aws::AwsEngine;
+    ///
+    /// let bucket = ResourceAbstract::<AwsEngine>::parse_lenient(" ARN|aws|s3|||bucket ", '|').unwrap();
+    /// assert_eq!(bucket.to_string(), "arn:aws:s3:::bucket");
+    /// ```
+    pub fn parse_lenient(input: &str, delimiter: char) -> Result<Self, IamError> {
+        let input = input.trim();
+        let normalized = match input.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("arn") => format!("arn{}", &input[3..]),
+            _ => input.to_string(),
         };
-        match (self.service.as_ref(), other.service.as_ref()) {
-            (Some(l), Some(r)) => {
-                if !l.matches(r)? {
-                    return Ok(false);
-                }
-            }
-            _ => {}
+        if delimiter == ':' {
+            return Self::from_str(&normalized);
         }
-        match (self.region.as_ref(), other.region.as_ref()) {
-            (Some(l), Some(r)) => {
-                if !l.matches(r)? {
-                    return Ok(false);
-                }
-            }
-            _ => {}
+        Self::from_str(&normalized.replace(delimiter, ":"))
+    }
+
+    /// Fills in [`EngineTrait::default_partition`] if this resource has no partition.
+    ///
+    /// Use this on request resources parsed from partial ARNs. Parsing and evaluation never
+    /// call it for you. Without it, a missing partition only matches policies that leave the
+    /// partition out too; with it, the request also matches policies that name the default
+    /// partition.
+    pub fn with_default_partition(mut self) -> Self {
+        if self.partition.is_none() {
+            self.partition = Engine::default_partition();
         }
-        match (self.account_id.as_ref(), other.account_id.as_ref()) {
-            (Some(l), Some(r)) => {
-                if !l.matches(r)? {
-                    return Ok(false);
-                }
+        self
+    }
+
+    /// Returns a copy of this resource with `${...}` policy variables substituted.
+    ///
+    /// The ARN is rendered, every placeholder such as `${aws:username}` is replaced with its
+    /// value from `variables`, and the result is parsed again. Substituted values are escaped so
+    /// that they only ever match literally. See [`UnknownVariable`] for placeholders that have
+    /// no value.
+    ///
+    /// # Errors
+    /// - `IamError::Variable` if a variable is unknown (under `UnknownVariable::Error`) or its
+    ///   value contains `:`.
+    /// - `IamError::MalformedArn` if the substituted ARN no longer parses.
+    pub fn substitute_variables(&self, variables: &HashMap<String, String>, unknown: UnknownVariable) -> Result<Self, IamError> {
+        let arn = self.to_string();
+        if !arn.contains("${") {
+            return Ok(self.clone());
+        }
+        ResourceAbstract::from_str(&variables::substitute(&arn, variables, unknown)?)
+    }
+
+    /// Matches `other` against this resource after substituting policy variables in this one.
+    ///
+    /// This is [`ResourceAbstract::substitute_variables`] followed by [`MatchesTrait::matches`].
+    pub fn matches_with_variables(
+        &self,
+        other: &ResourceAbstract<Engine>,
+        variables: &HashMap<String, String>,
+        unknown: UnknownVariable,
+    ) -> Result<bool, IamError> {
+        Ok(self.substitute_variables(variables, unknown)?.matches(other)?)
+    }
+
+    /// Renders the ARN with the account id replaced by `***`, for use in logs.
+    ///
+    /// Every other segment is rendered as in [`fmt::Display`]. A missing account id stays empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::ResourceAbstract;
+    /// use rust_iam::aws::AwsEngine;
+    ///
+    /// let resource: ResourceAbstract<AwsEngine> = "arn:aws:ec2:us-east-1:123456789012:instance:i-1".parse().unwrap();
+    /// assert_eq!(resource.redacted(), "arn:aws:ec2:us-east-1:***:instance:i-1");
+    /// ```
+    pub fn redacted(&self) -> String {
+        self.redact(false)
+    }
+
+    /// Like [`ResourceAbstract::redacted`], but also replaces the resource id, the qualifier and
+    /// any trailing segments with `***`.
+    pub fn redacted_with_resource_id(&self) -> String {
+        self.redact(true)
+    }
+
+    fn redact(&self, mask_resource_id: bool) -> String {
+        const MASK: &str = "***";
+        let mut segments = self.arn_segments();
+        if segments.is_empty() {
+            return "*".to_string();
+        }
+        for (index, segment) in segments.iter_mut().enumerate() {
+            let masked = index == ArnSegment::AccountId.index()
+                || (mask_resource_id && index >= ArnSegment::ResourceId.index().min(Engine::ARN_SEGMENTS));
+            if masked && !segment.is_empty() {
+                *segment = MASK.to_string();
             }
-            _ => {}
         }
-        match (self.resource_type.as_ref(), other.resource_type.as_ref()) {
-            (Some(l), Some(r)) => {
-                if !l.matches(r)? {
-                    return Ok(false);
-                }
+        format!("arn:{}", segments.join(":"))
+    }
+
+    /// Returns the segments rendered after the `arn:` prefix.
+    ///
+    /// Interior empty segments are kept (e.g. the region of `arn:aws:iam::123:role/x`), but
+    /// empty segments at the end are trimmed, as AWS never writes trailing colons. Nothing is
+    /// trimmed when a qualifier or trailing segment follows, since their position matters.
+    fn arn_segments(&self) -> Vec<String> {
+        let typed = self.segment_strings().map(Option::unwrap_or_default);
+        let mut segments = typed[..Engine::ARN_SEGMENTS.min(typed.len())].to_vec();
+        if self.qualifier.is_some() || !self.trailing.is_empty() {
+            segments.push(self.qualifier.clone().unwrap_or_default());
+            segments.extend(self.trailing.iter().cloned());
+        } else {
+            while segments.last().is_some_and(String::is_empty) {
+                segments.pop();
             }
-            _ => {}
         }
-        match (self.resource_id.as_ref(), other.resource_id.as_ref()) {
-            (Some(l), Some(r)) => {
-                if !l.matches(r)? {
-                    return Ok(false);
+        segments
+    }
+
+    /// Builds a resource with every ARN segment set.
+    ///
+    /// This is a shorthand for wrapping each value in `Some` when all segments are known.
+    /// The qualifier is left unset.
+    ///
+    /// # Examples
+    /// ```
+    /// use rust_iam::ResourceAbstract;
+    /// use rust_iam::aws::{AwsEngine, AwsPartition, AwsRegion, WildString};
+    ///
+    /// let instance = ResourceAbstract::<AwsEngine>::full(
+    ///     AwsPartition::Aws,
+    ///     WildString("ec2".to_string()),
+    ///     AwsRegion::UsEastNVirginia.into(),
+    ///     WildString("123456789012".to_string()),
+    ///     WildString("instance".to_string()),
+    ///     "i-0abcd1234efgh5678".parse().unwrap(),
+    /// );
+    /// assert_eq!(instance.to_string(), "arn:aws:ec2:us-east-1:123456789012:instance:i-0abcd1234efgh5678");
+    /// ```
+    pub fn full(
+        partition: Engine::Partition,
+        service: Engine::Service,
+        region: Engine::Region,
+        account_id: Engine::AccountID,
+        resource_type: Engine::ResourceType,
+        resource_id: Engine::ResourceID,
+    ) -> Self {
+        ResourceAbstract {
+            partition: Some(partition),
+            service: Some(service),
+            region: Some(region),
+            account_id: Some(account_id),
+            resource_type: Some(resource_type),
+            resource_id: Some(resource_id),
+            qualifier: None,
+            trailing: Vec::new(),
+        }
+    }
+
+    /// Computes the most specific single pattern that covers every resource in `resources`.
+    ///
+    /// Segments on which all resources agree are kept. A segment that differs becomes the
+    /// longest common literal prefix followed by `*` (e.g. `bucket/a` and `bucket/b` collapse
+    /// to `bucket/*`), or `*` when there is no usable prefix. A segment some resources leave
+    /// unset is left unset, since it already matches anything. Resources of different services
+    /// are never merged.
+    ///
+    /// # Parameters
+    /// - `resources`: The resources to cover.
+    ///
+    /// # Returns
+    /// - `Some(pattern)` that matches every resource in `resources`.
+    /// - `None` if `resources` is empty or names more than one service.
+    pub fn covering(resources: &[ResourceAbstract<Engine>]) -> Option<Self> {
+        let first = resources.first()?;
+        if resources.iter().any(|r| r.service != first.service) {
+            return None;
+        }
+        let qualifiers: Vec<Option<&String>> = resources.iter().map(|r| r.qualifier.as_ref()).collect();
+        let qualifier = if qualifiers.iter().all(|q| *q == qualifiers[0]) {
+            first.qualifier.clone()
+        } else {
+            qualifiers
+                .iter()
+                .copied()
+                .collect::<Option<Vec<&String>>>()
+                .map(|all| format!("{}*", common_literal_prefix(all.iter().map(|q| q.as_str()))))
+                .filter(|pattern| pattern != "*")
+        };
+        let trailing = if resources.iter().all(|r| r.trailing == first.trailing) {
+            first.trailing.clone()
+        } else {
+            Vec::new()
+        };
+        Some(ResourceAbstract {
+            partition: cover_segment(resources.iter().map(|r| r.partition.as_ref())),
+            service: first.service.clone(),
+            region: cover_segment(resources.iter().map(|r| r.region.as_ref())),
+            account_id: cover_segment(resources.iter().map(|r| r.account_id.as_ref())),
+            resource_type: cover_segment(resources.iter().map(|r| r.resource_type.as_ref())),
+            resource_id: cover_segment(resources.iter().map(|r| r.resource_id.as_ref())),
+            qualifier,
+            trailing,
+        })
+    }
+
+    /// Returns `true` if no segment, qualifier or trailing segment contains a `*` or `?`.
+    ///
+    /// Request resources should be concrete; a wildcard in a request turns both sides of a
+    /// comparison into patterns and can match in surprising ways.
+    pub fn is_concrete(&self) -> bool {
+        self.segment_strings()
+            .iter()
+            .flatten()
+            .chain(self.qualifier.iter())
+            .chain(self.trailing.iter())
+            .all(|segment| !segment.contains(['*', '?']))
+    }
+
+    /// Matches `other` against this resource and reports whether it matched literally.
+    ///
+    /// # Returns
+    /// - `Ok(Some(MatchKind::Exact))` if this resource is concrete and names exactly `other`.
+    /// - `Ok(Some(MatchKind::Wildcard))` if it matched through wildcards or unset segments.
+    /// - `Ok(None)` if it does not match.
+    /// - `Err` if a segment could not be matched.
+    pub fn match_kind(&self, other: &ResourceAbstract<Engine>) -> Result<Option<MatchKind>, &'static str> {
+        if !self.matches(other)? {
+            return Ok(None);
+        }
+        let exact = self.is_concrete()
+            && self.segment_strings() == other.segment_strings()
+            && self.qualifier == other.qualifier
+            && self.trailing == other.trailing;
+        Ok(Some(if exact { MatchKind::Exact } else { MatchKind::Wildcard }))
+    }
+
+    /// Counts the segments this resource names literally and that equal the same segment of
+    /// `other`. Unset and wildcard segments never count.
+    pub(crate) fn exact_segments(&self, other: &ResourceAbstract<Engine>) -> usize {
+        self.segment_strings()
+            .into_iter()
+            .zip(other.segment_strings())
+            .filter(|(l, r)| matches!((l, r), (Some(l), Some(r)) if l == r && !l.contains(['*', '?'])))
+            .count()
+    }
+
+    /// Reports, segment by segment, how `other` compares against this resource.
+    ///
+    /// This uses the same rules as [`MatchesTrait::matches`] but does not stop at the first
+    /// mismatch, so it shows every segment that would have to change. Trailing segments are
+    /// not reported.
+    ///
+    /// # Parameters
+    /// - `other`: The request resource to compare.
+    ///
+    /// # Returns
+    /// A [`SegmentMatchReport`] with one outcome per segment.
+    pub fn match_detail(&self, other: &ResourceAbstract<Engine>) -> SegmentMatchReport {
+        SegmentMatchReport {
+            segments: [
+                SegmentMatch::of(&self.partition, &other.partition),
+                SegmentMatch::of(&self.service, &other.service),
+                SegmentMatch::of(&self.region, &other.region),
+                SegmentMatch::of(&self.account_id, &other.account_id),
+                SegmentMatch::of(&self.resource_type, &other.resource_type),
+                SegmentMatch::of(&self.resource_id, &other.resource_id),
+            ],
+            qualifier: SegmentMatch::of(
+                &self.qualifier.as_deref().map(Glob),
+                &other.qualifier.as_deref().map(Glob),
+            ),
+        }
+    }
+
+    /// Matches `other` against this resource using the given [`ResourceMatchMode`].
+    ///
+    /// `ResourceMatchMode::PerSegment` behaves exactly like [`MatchesTrait::matches`].
+    ///
+    /// # Parameters
+    /// - `other`: The request resource to test.
+    /// - `mode`: How the two resources are compared.
+    ///
+    /// # Returns
+    /// - `Ok(true)` if `other` matches this resource.
+    /// - `Err` if this resource could not be compiled into a wildcard pattern, or, in
+    ///   `ResourceMatchMode::Strict`, if `other` leaves out a segment or qualifier this
+    ///   resource sets.
+    pub fn matches_with_mode(&self, other: &ResourceAbstract<Engine>, mode: ResourceMatchMode) -> Result<bool, &'static str> {
+        match mode {
+            ResourceMatchMode::PerSegment => self.matches(other),
+            ResourceMatchMode::Strict => {
+                let under_specified = self
+                    .segment_strings()
+                    .iter()
+                    .zip(other.segment_strings().iter())
+                    .any(|(policy, request)| policy.is_some() && request.is_none())
+                    || (self.qualifier.is_some() && other.qualifier.is_none());
+                if under_specified {
+                    return Err("Under-specified request resource");
                 }
+                self.matches(other)
+            }
+            ResourceMatchMode::WholeArn => {
+                let pattern = self.to_string();
+                let value = other.to_string();
+                let pattern = Wildcard::new(pattern.trim_end_matches(':').as_bytes())
+                    .map_err(|_| "Failed to compile wildcard pattern")?;
+                Ok(pattern.is_match(value.trim_end_matches(':').as_bytes()))
             }
-            _ => {}
         }
-        Ok(true)
     }
 }
 
+/// Returns the longest prefix shared by all `values` that contains no wildcard characters.
+fn common_literal_prefix<'a>(mut values: impl Iterator<Item = &'a str>) -> &'a str {
+    let Some(first) = values.next() else {
+        return "";
+    };
+    let mut len = first.find(['*', '?', '\\']).unwrap_or(first.len());
+    for value in values {
+        len = first[..len]
+            .char_indices()
+            .zip(value.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(value.len()), |((i, _), _)| i);
+    }
+    &first[..len]
+}
+
+/// Covers one typed segment for [`ResourceAbstract::covering`].
+///
+/// Tries `<common prefix>*`, then `*`, and checks the candidate really matches every value,
+/// since typed segments may parse patterns differently from plain globs. Falls back to
+/// leaving the segment unset.
+fn cover_segment<'a, T>(values: impl Iterator<Item = Option<&'a T>> + Clone) -> Option<T>
+where
+    T: FromStr + ToString + PartialEq + Clone + MatchesTrait<bool> + 'a,
+{
+    let mut all = values.clone();
+    let first = all.next()?;
+    if all.all(|v| v == first) {
+        return first.cloned();
+    }
+    let values: Vec<&T> = values.collect::<Option<Vec<&T>>>()?;
+    let strings: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    let prefix = common_literal_prefix(strings.iter().map(String::as_str));
+    [format!("{}*", prefix), "*".to_string()]
+        .iter()
+        .filter_map(|candidate| T::from_str(candidate).ok())
+        .find(|candidate| values.iter().all(|v| matches!(candidate.matches(v), Ok(true))))
+}
+
 #[cfg(test)]
-mod tests {}
\ No newline at end of file
+mod tests {
+    use super::*;
+    use crate::aws::{AwsEngine, AwsPartition, WildString};
+
+    const LAMBDA: &str = "arn:aws:lambda:us-east-1:123456789012:function:my-function";
+
+    fn parse(s: &str) -> ResourceAbstract<AwsEngine> {
+        ResourceAbstract::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_match_kind() {
+        let object = parse("arn:aws:s3:::bucket/key");
+        assert_eq!(parse("arn:aws:s3:::bucket/key").match_kind(&object), Ok(Some(MatchKind::Exact)));
+        assert_eq!(parse("arn:aws:s3:::bucket/*").match_kind(&object), Ok(Some(MatchKind::Wildcard)));
+        assert_eq!(parse("arn:aws:s3:::bucket/*").match_kind(&parse("arn:aws:s3:::bucket/*")), Ok(Some(MatchKind::Wildcard)));
+        assert_eq!(parse("arn:aws:s3:*:::bucket/key").match_kind(&object), Ok(None));
+        assert_eq!(parse("*").match_kind(&object), Ok(Some(MatchKind::Wildcard)));
+        assert_eq!(parse("arn:aws:s3:::other/key").match_kind(&object), Ok(None));
+    }
+
+    #[test]
+    fn test_from_segments() {
+        let segments = ["aws", "lambda", "us-east-1", "123456789012", "function", "my-function"];
+        assert_eq!(ResourceAbstract::from_segments(&segments), Ok(parse(LAMBDA)));
+        assert_eq!(
+            ResourceAbstract::<AwsEngine>::from_segments(&["aws", "s3", "", "", "bucket"]),
+            Ok(parse("arn:aws:s3:::bucket"))
+        );
+
+        // The caller kept the colon inside the resource id, which splitting would have broken up.
+        let merged = ResourceAbstract::<AwsEngine>::from_segments(&segments[..4].iter().copied().chain(["function", "app:v1:beta"]).collect::<Vec<_>>()).unwrap();
+        assert_eq!(merged.resource_id.as_ref().map(ToString::to_string).as_deref(), Some("app:v1:beta"));
+        assert_eq!(merged.qualifier, None);
+        assert_eq!(parse(&format!("{LAMBDA}:v1")).qualifier.as_deref(), Some("v1"));
+
+        let err = ResourceAbstract::<AwsEngine>::from_segments(&["aws", "ec2", "zz", "123456789012", "instance", "i-1"]).unwrap_err();
+        let from_str = ResourceAbstract::<AwsEngine>::from_str("arn:aws:ec2:zz:123456789012:instance:i-1").unwrap_err();
+        assert_eq!(err, from_str);
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let expected = parse(LAMBDA);
+        assert_eq!(ResourceAbstract::parse_lenient(&LAMBDA.replacen("arn", "ARN", 1), ':'), Ok(expected.clone()));
+        assert_eq!(ResourceAbstract::parse_lenient(&format!("  Arn{}\n", &LAMBDA[3..]), ':'), Ok(expected.clone()));
+        assert_eq!(ResourceAbstract::parse_lenient(&LAMBDA.replace(':', "/"), '/'), Ok(expected));
+        assert_eq!(
+            ResourceAbstract::<AwsEngine>::parse_lenient("ARN;aws;s3;;;bucket", ';'),
+            Ok(parse("arn:aws:s3:::bucket"))
+        );
+        assert!(ResourceAbstract::<AwsEngine>::parse_lenient("urn:aws:s3:::bucket", ':').is_err());
+        assert!(ResourceAbstract::<AwsEngine>::from_str(&LAMBDA.replacen("arn", "ARN", 1)).is_err());
+    }
+
+    #[test]
+    fn test_qualifier_is_parsed() {
+        assert_eq!(parse(LAMBDA).qualifier, None);
+        assert_eq!(parse(&format!("{LAMBDA}:PROD")).qualifier, Some("PROD".to_string()));
+        assert_eq!(parse(&format!("{LAMBDA}:1")).qualifier, Some("1".to_string()));
+    }
+
+    #[test]
+    fn test_qualifier_round_trips() {
+        let json = serde_json::to_string(&parse(&format!("{LAMBDA}:PROD"))).unwrap();
+        assert!(json.ends_with(":function:my-function:PROD\""), "{json}");
+    }
+
+    #[test]
+    fn test_unqualified_policy_matches_any_qualifier() {
+        let policy = parse(LAMBDA);
+        assert_eq!(policy.matches(&parse(LAMBDA)), Ok(true));
+        assert_eq!(policy.matches(&parse(&format!("{LAMBDA}:PROD"))), Ok(true));
+        assert_eq!(policy.matches(&parse(&format!("{LAMBDA}:1"))), Ok(true));
+    }
+
+    #[test]
+    fn test_qualified_policy_matches_same_qualifier_only() {
+        let policy = parse(&format!("{LAMBDA}:PROD"));
+        assert_eq!(policy.matches(&parse(&format!("{LAMBDA}:PROD"))), Ok(true));
+        assert_eq!(policy.matches(&parse(&format!("{LAMBDA}:1"))), Ok(false));
+        assert_eq!(parse(&format!("{LAMBDA}:*")).matches(&parse(&format!("{LAMBDA}:1"))), Ok(true));
+        assert_eq!(policy.matches(&parse(LAMBDA)), Ok(false));
+        assert_eq!(policy.match_detail(&parse(LAMBDA)).qualifier, SegmentMatch::Mismatched);
+        assert_eq!(parse(LAMBDA).match_detail(&parse(&format!("{LAMBDA}:1"))).qualifier, SegmentMatch::Skipped);
+    }
+
+    #[test]
+    fn test_empty_segments_parse_as_none() {
+        let bucket = parse("arn:aws:s3:::my-bucket/*");
+        assert_eq!(bucket.region, None);
+        assert_eq!(bucket.account_id, None);
+        assert_eq!(bucket.resource_type.map(|t| t.0), Some("my-bucket/*".to_string()));
+        assert_eq!(bucket.resource_id, None);
+    }
+
+    #[test]
+    fn test_whole_arn_wildcard_spans_segments() {
+        let policy = parse("arn:aws:s3:::*.csv");
+        let request = parse("arn:aws:s3:::my-bucket/reports:2024.csv");
+
+        assert_eq!(policy.matches_with_mode(&request, ResourceMatchMode::PerSegment), Ok(false));
+        assert_eq!(policy.matches_with_mode(&request, ResourceMatchMode::WholeArn), Ok(true));
+        assert_eq!(policy.matches_with_mode(&parse("arn:aws:s3:::my-bucket/report.json"), ResourceMatchMode::WholeArn), Ok(false));
+    }
+
+    #[test]
+    fn test_match_options_relax_account_and_region() {
+        let policy = parse("arn:aws:lambda:us-east-1:111111111111:function:my-function");
+        let other_account = parse("arn:aws:lambda:us-east-1:222222222222:function:my-function");
+        let other_region = parse("arn:aws:lambda:eu-west-1:111111111111:function:my-function");
+        let elsewhere = parse("arn:aws:lambda:eu-west-1:222222222222:function:my-function");
+        let no_account = parse("arn:aws:lambda:us-east-1::function:my-function");
+
+        let accounts = MatchOptions { ignore_account: true, ..Default::default() };
+        let regions = MatchOptions { ignore_region: true, ..Default::default() };
+        let both = MatchOptions { ignore_account: true, ignore_region: true };
+
+        for request in [&other_account, &other_region, &elsewhere, &no_account] {
+            assert_eq!(policy.matches_with_options(request, MatchOptions::default()), Ok(false), "{request}");
+        }
+        assert_eq!(policy.matches_with_options(&other_account, accounts), Ok(true));
+        assert_eq!(policy.matches_with_options(&no_account, accounts), Ok(true));
+        assert_eq!(policy.matches_with_options(&other_region, accounts), Ok(false));
+        assert_eq!(policy.matches_with_options(&other_region, regions), Ok(true));
+        assert_eq!(policy.matches_with_options(&elsewhere, regions), Ok(false));
+        assert_eq!(policy.matches_with_options(&elsewhere, both), Ok(true));
+        assert_eq!(policy.matches_with_options(&parse("arn:aws:lambda:eu-west-1:222222222222:function:other"), both), Ok(false));
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_under_specified_requests() {
+        let policy = parse("arn:aws:lambda:us-east-1:123456789012:function:my-function");
+        let vague = parse("arn:aws:lambda:us-east-1::function:my-function");
+
+        assert_eq!(policy.matches_with_mode(&vague, ResourceMatchMode::PerSegment), Ok(false));
+        assert_eq!(policy.matches_with_mode(&vague, ResourceMatchMode::Strict), Err("Under-specified request resource"));
+        assert_eq!(policy.matches_with_mode(&parse(LAMBDA), ResourceMatchMode::Strict), Ok(true));
+        assert_eq!(parse("arn:aws:lambda:::function:*").matches_with_mode(&vague, ResourceMatchMode::Strict), Ok(true));
+
+        let versioned = parse(&format!("{LAMBDA}:PROD"));
+        assert_eq!(versioned.matches_with_mode(&parse(LAMBDA), ResourceMatchMode::PerSegment), Ok(false));
+        assert!(versioned.matches_with_mode(&parse(LAMBDA), ResourceMatchMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_match_detail_reports_each_segment() {
+        let policy = parse("arn:aws:lambda::123456789012:function:other-*:PROD");
+        let request = parse(&format!("{LAMBDA}:PROD"));
+        let report = policy.match_detail(&request);
+
+        assert_eq!(report.segments, [
+            SegmentMatch::Matched,
+            SegmentMatch::Matched,
+            SegmentMatch::Skipped,
+            SegmentMatch::Matched,
+            SegmentMatch::Matched,
+            SegmentMatch::Mismatched,
+        ]);
+        assert_eq!(report.qualifier, SegmentMatch::Matched);
+        assert_eq!(report.first_failure(), Some(5));
+        assert_eq!(policy.matches(&request), Ok(false));
+
+        assert_eq!(parse(LAMBDA).match_detail(&parse(LAMBDA)).first_failure(), None);
+    }
+
+    #[test]
+    fn test_whole_arn_matches_trailing_wildcard() {
+        let policy = parse("arn:aws:s3:::my-bucket/*");
+        assert_eq!(policy.matches_with_mode(&parse("arn:aws:s3:::my-bucket/a/b"), ResourceMatchMode::WholeArn), Ok(true));
+        assert_eq!(policy.matches_with_mode(&parse("arn:aws:s3:::other-bucket/a"), ResourceMatchMode::WholeArn), Ok(false));
+    }
+
+    #[test]
+    fn test_equality_with_arn_string() {
+        let resource = parse("arn:aws:lambda:us-east-1:123456789012:function:my-function:PROD");
+        assert!(resource == "arn:aws:lambda:us-east-1:123456789012:function:my-function:PROD");
+        assert!(resource == *"arn:aws:lambda:us-east-1:123456789012:function:my-function:PROD");
+        assert!(resource != "arn:aws:lambda:us-east-1:123456789012:function:my-function:DEV");
+        assert!(parse("arn:aws:s3:::*:*") != "arn:aws:s3:::bucket:key");
+    }
+
+    #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+    struct FiveSegmentEngine;
+
+    impl EngineTrait for FiveSegmentEngine {
+        type Action = WildString;
+        type Partition = WildString;
+        type Service = WildString;
+        type Region = WildString;
+        type AccountID = WildString;
+        type ResourceType = WildString;
+        type ResourceID = WildString;
+        const ARN_SEGMENTS: usize = 5;
+
+        fn name() -> &'static str {
+            "five-segment"
+        }
+
+        fn resource_format_example() -> &'static str {
+            "arn:corp:db:eu:team-a:table-1"
+        }
+    }
+
+    #[test]
+    fn test_engine_with_fewer_segments() {
+        let resource = ResourceAbstract::<FiveSegmentEngine>::from_str("arn:corp:db:eu:team-a:table-1:v2").unwrap();
+        assert_eq!(resource.resource_type, Some(WildString("table-1".to_string())));
+        assert_eq!(resource.resource_id, None);
+        assert_eq!(resource.qualifier.as_deref(), Some("v2"));
+        assert_eq!(resource.to_string(), "arn:corp:db:eu:team-a:table-1:v2");
+
+        let short = ResourceAbstract::<FiveSegmentEngine>::from_str("arn:corp:db:eu:team-a:table-1").unwrap();
+        assert_eq!(short.to_string(), "arn:corp:db:eu:team-a:table-1");
+    }
+
+    #[test]
+    fn test_malformed_arn_reports_offset_and_segment() {
+        let err = ResourceAbstract::<AwsEngine>::from_str("arn:aws:ec2:zz:123456789012:instance:i-1").unwrap_err();
+        assert_eq!(err, IamError::MalformedArn { offset: 12, segment: 3, reason: "Invalid Region" });
+        assert_eq!(err.to_string(), "malformed ARN at byte 12 (segment 3): Invalid Region");
+
+        let err = ResourceAbstract::<AwsEngine>::from_str("urn:aws:s3:::bucket").unwrap_err();
+        assert!(matches!(err, IamError::MalformedArn { offset: 0, segment: 0, .. }));
+    }
+
+    #[test]
+    fn test_deserialize_object_form() {
+        let resource: ResourceAbstract<AwsEngine> =
+            serde_json::from_str(r#"{"service": "s3", "resourceType": "bucket"}"#).unwrap();
+        assert_eq!(resource, parse("arn::s3:::bucket"));
+
+        let resource: ResourceAbstract<AwsEngine> = serde_json::from_str(
+            r#"{"partition": "aws", "service": "lambda", "region": "us-east-1", "accountId": "123456789012",
+                "resourceType": "function", "resourceId": "my-fn", "qualifier": "prod"}"#,
+        )
+        .unwrap();
+        assert_eq!(resource, parse("arn:aws:lambda:us-east-1:123456789012:function:my-fn:prod"));
+
+        // The string form still works and serialization stays string-form.
+        let resource: ResourceAbstract<AwsEngine> = serde_json::from_str(r#""arn:aws:s3:::bucket""#).unwrap();
+        assert_eq!(serde_json::to_string(&resource).unwrap(), serde_json::to_string(&parse("arn:aws:s3:::bucket")).unwrap());
+
+        let err = serde_json::from_str::<ResourceAbstract<AwsEngine>>(r#"{"bucket": "b"}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown field `bucket`"), "{err}");
+        let err = serde_json::from_str::<ResourceAbstract<AwsEngine>>(r#"{"region": "zz"}"#).unwrap_err();
+        assert!(err.to_string().contains("Invalid Region"), "{err}");
+    }
+
+    #[test]
+    fn test_redacted_masks_account_id() {
+        let resource = parse("arn:aws:lambda:us-east-1:123456789012:function:my-fn:prod");
+        assert_eq!(resource.redacted(), "arn:aws:lambda:us-east-1:***:function:my-fn:prod");
+        assert_eq!(resource.redacted_with_resource_id(), "arn:aws:lambda:us-east-1:***:function:***:***");
+
+        let bucket = parse("arn:aws:s3:::bucket");
+        assert_eq!(bucket.redacted(), bucket.to_string());
+    }
+
+    #[test]
+    fn test_default_partition_pins_requests() {
+        let aws_policy = parse("arn:aws:s3:::bucket");
+        let china_policy = parse("arn:aws-cn:s3:::bucket");
+
+        let bare = parse("arn::s3:::bucket");
+        assert_eq!(aws_policy.matches(&bare), Ok(false));
+        assert_eq!(china_policy.matches(&bare), Ok(false));
+
+        let pinned = bare.with_default_partition();
+        assert_eq!(pinned.partition, Some(AwsPartition::Aws));
+        assert_eq!(aws_policy.matches(&pinned), Ok(true));
+        assert_eq!(china_policy.matches(&pinned), Ok(false));
+        assert_eq!(parse("arn::s3:::bucket").matches(&pinned), Ok(true));
+
+        let explicit = parse("arn:aws-cn:s3:::bucket").with_default_partition();
+        assert_eq!(explicit.partition, Some(AwsPartition::AwsChina));
+        assert_eq!(ResourceAbstract::<FiveSegmentEngine>::from_str("arn::db:eu:team-a:t").unwrap().with_default_partition().partition, None);
+    }
+
+    #[test]
+    fn test_trailing_segments_round_trip() {
+        let arn = "arn:aws:lambda:us-east-1:123456789012:function:my-fn:prod:extra";
+        let resource = parse(arn);
+        assert_eq!(resource.qualifier.as_deref(), Some("prod"));
+        assert_eq!(resource.trailing, vec!["extra".to_string()]);
+        assert_eq!(resource.to_string(), arn);
+
+        let sparse = "arn:aws:lambda:us-east-1:123456789012:function:my-fn::a::b";
+        assert_eq!(parse(sparse).to_string(), sparse);
+        assert_eq!(parse(sparse).redacted_with_resource_id(), "arn:aws:lambda:us-east-1:***:function:***::***::***");
+    }
+
+    #[test]
+    fn test_trailing_segments_only_compared_when_both_present() {
+        let request = parse(&format!("{LAMBDA}:prod:extra"));
+        assert_eq!(parse(&format!("{LAMBDA}:prod")).matches(&request), Ok(true));
+        assert_eq!(parse(&format!("{LAMBDA}:prod:ex*")).matches(&request), Ok(true));
+        assert_eq!(parse(&format!("{LAMBDA}:prod:other")).matches(&request), Ok(false));
+        assert_eq!(parse(&format!("{LAMBDA}:prod:extra:more")).matches(&request), Ok(false));
+        assert_eq!(parse(&format!("{LAMBDA}:prod:extra")).matches(&parse(&format!("{LAMBDA}:prod"))), Ok(true));
+    }
+
+    #[test]
+    fn test_trailing_empty_segments_are_trimmed() {
+        let global = "arn:aws:iam::123456789012:role/admin";
+        assert_eq!(parse(global).to_string(), global);
+        assert_eq!(parse("arn:aws:s3:::bucket").to_string(), "arn:aws:s3:::bucket");
+        assert_eq!(parse("arn:aws:s3:::bucket:").to_string(), "arn:aws:s3:::bucket");
+        assert_eq!(parse("arn:aws:s3::::").to_string(), "arn:aws:s3");
+        assert_eq!(parse("arn:aws:s3").to_string(), "arn:aws:s3");
+        assert_eq!(parse("arn:::::").to_string(), "*");
+
+        // Matching is unaffected: the trimmed form parses back to the same resource.
+        for arn in [global, "arn:aws:s3::::", "arn:aws:lambda:us-east-1:123456789012:function:f::x"] {
+            assert_eq!(parse(&parse(arn).to_string()), parse(arn));
+        }
+    }
+
+    #[test]
+    fn test_variable_substitution() {
+        let policy = parse("arn:aws:s3:::bucket/home/${aws:username}/*");
+        let variables = HashMap::from([("aws:username".to_string(), "alice".to_string())]);
+        let own = parse("arn:aws:s3:::bucket/home/alice/notes.txt");
+        let other = parse("arn:aws:s3:::bucket/home/bob/notes.txt");
+
+        assert_eq!(policy.to_string(), "arn:aws:s3:::bucket/home/${aws:username}/*");
+        assert_eq!(policy.matches_with_variables(&own, &variables, UnknownVariable::Error), Ok(true));
+        assert_eq!(policy.matches_with_variables(&other, &variables, UnknownVariable::Error), Ok(false));
+
+        let unknown = parse("arn:aws:s3:::bucket/home/${aws:userid}/*");
+        assert!(matches!(
+            unknown.matches_with_variables(&own, &variables, UnknownVariable::Error),
+            Err(IamError::Variable { .. })
+        ));
+        assert_eq!(unknown.matches_with_variables(&own, &variables, UnknownVariable::Keep), Ok(false));
+    }
+
+    #[test]
+    fn test_star_shorthand_matches_everything() {
+        let any = parse("*");
+        assert_eq!(any, parse("arn:::::"));
+        assert_eq!(any.to_string(), "*");
+        assert_eq!(any.matches(&parse(LAMBDA)), Ok(true));
+        assert_eq!(any.matches(&parse("arn:aws:s3:::bucket/key")), Ok(true));
+        assert!(ResourceAbstract::<AwsEngine>::from_str("**").is_err());
+    }
+
+    #[test]
+    fn test_star_spans_slashes_in_arns() {
+        let objects = parse("arn:aws:s3:::bucket/*");
+        assert_eq!(objects.matches(&parse("arn:aws:s3:::bucket/deep/nested/key")), Ok(true));
+        assert_eq!(objects.matches(&parse("arn:aws:s3:::other/deep/nested/key")), Ok(false));
+        let files = parse("arn:aws:ec2:us-east-1:123456789012:file:share/*/key");
+        assert_eq!(files.matches(&parse("arn:aws:ec2:us-east-1:123456789012:file:share/deep/nested/key")), Ok(true));
+    }
+
+    #[test]
+    fn test_iterating_segments() {
+        let segments: Vec<(ArnSegment, Option<String>)> = parse("arn:aws:s3:::bucket/key").segments().collect();
+        assert_eq!(segments, vec![
+            (ArnSegment::Partition, Some("aws".to_string())),
+            (ArnSegment::Service, Some("s3".to_string())),
+            (ArnSegment::Region, None),
+            (ArnSegment::AccountId, None),
+            (ArnSegment::ResourceType, Some("bucket/key".to_string())),
+            (ArnSegment::ResourceId, None),
+        ]);
+        let lambda = parse(LAMBDA);
+        for (index, (segment, value)) in lambda.segments().enumerate() {
+            assert_eq!(segment.index(), index);
+            assert_eq!(value, lambda.segment_str(segment));
+        }
+        assert_eq!(lambda.segment_str(ArnSegment::ResourceId).as_deref(), Some("my-function"));
+    }
+
+    #[test]
+    fn test_covering_collapses_same_service() {
+        let objects = [parse("arn:aws:s3:::bucket/a"), parse("arn:aws:s3:::bucket/b")];
+        let cover = ResourceAbstract::covering(&objects).unwrap();
+        assert_eq!(cover.to_string(), "arn:aws:s3:::bucket/*");
+        assert!(objects.iter().all(|o| cover.matches(o) == Ok(true)));
+
+        let instances = [
+            parse("arn:aws:ec2:us-east-1:123456789012:instance:i-0abc"),
+            parse("arn:aws:ec2:eu-west-1:123456789012:instance:i-0abd"),
+        ];
+        let cover = ResourceAbstract::covering(&instances).unwrap();
+        assert_eq!(cover.to_string(), "arn:aws:ec2:*:123456789012:instance:i-0ab*");
+        assert!(instances.iter().all(|i| cover.matches(i) == Ok(true)));
+
+        assert_eq!(ResourceAbstract::covering(&[parse(LAMBDA)]), Some(parse(LAMBDA)));
+        let versions = [parse(&format!("{LAMBDA}:1")), parse(&format!("{LAMBDA}:2"))];
+        assert_eq!(ResourceAbstract::covering(&versions).unwrap().qualifier, None);
+    }
+
+    #[test]
+    fn test_covering_rejects_mixed_services() {
+        let mixed = [parse("arn:aws:s3:::bucket/a"), parse(LAMBDA)];
+        assert_eq!(ResourceAbstract::covering(&mixed), None);
+        assert_eq!(ResourceAbstract::<AwsEngine>::covering(&[]), None);
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use crate::engine::EngineTrait;
+use crate::error::IamError;
+use crate::resource::ResourceAbstract;
+use crate::traits::MatchesTrait;
+use wildcard::Wildcard;
+
+/// A borrowed view of an ARN that keeps every segment as a slice of the original string.
+///
+/// Parsing a `ResourceRef` only splits the input, so it never allocates, and matching two
+/// `ResourceRef`s compares the raw segment text with the same wildcard rules as the owned
+/// types. Use [`ResourceRef::to_resource`] to get a typed [`ResourceAbstract`] when needed.
+///
+/// Because segments are compared as text, values that only become equal after typed parsing
+/// (a region alias such as `virginia`, or a percent-encoded resource id) do not match their
+/// canonical form here. For canonical ARNs the result agrees with [`ResourceAbstract`] matching.
+///
+/// # Examples
+/// ```
+/// use rust_iam::ResourceRef;
+/// use rust_iam::aws::AwsEngine;
+/// use rust_iam::traits::MatchesTrait;
+///
+/// let pattern = ResourceRef::<AwsEngine>::parse("arn:aws:s3:::bucket/*").unwrap();
+/// let request = ResourceRef::<AwsEngine>::parse("arn:aws:s3:::bucket/key").unwrap();
+/// assert_eq!(pattern.matches(&request), Ok(true));
+/// ```
+pub struct ResourceRef<'a, Engine: EngineTrait> {
+    source: &'a str,
+    // The typed segments; only the first `Engine::ARN_SEGMENTS` are ever set.
+    segments: [Option<&'a str>; 6],
+    qualifier: Option<&'a str>,
+    // Everything after the qualifier, split on `:` only when matching.
+    trailing: Option<&'a str>,
+    _engine: PhantomData<fn() -> Engine>,
+}
+
+impl<'a, Engine: EngineTrait> ResourceRef<'a, Engine> {
+    /// Splits an ARN into borrowed segments without validating them.
+    ///
+    /// Empty segments are treated as missing, like [`ResourceAbstract::from_str`], and the
+    /// shorthand `*` leaves every segment unset.
+    ///
+    /// # Errors
+    /// Returns `IamError::MalformedArn` if the input does not start with `arn:`.
+    pub fn parse(source: &'a str) -> Result<Self, IamError> {
+        if source == "*" {
+            return Ok(ResourceRef { source, segments: [None; 6], qualifier: None, trailing: None, _engine: PhantomData });
+        }
+        if !source.starts_with("arn:") {
+            return Err(IamError::MalformedArn {
+                offset: 0,
+                segment: 0,
+                reason: "Invalid resource format: Resource name should start with 'arn:'",
+            });
+        }
+
+        // `arn`, the typed segments, the qualifier, and the rest of the ARN unsplit.
+        let mut split = source.splitn(Engine::ARN_SEGMENTS.min(6) + 3, ':').skip(1);
+        let mut segments = [None; 6];
+        for (slot, segment) in segments.iter_mut().zip(split.by_ref().take(Engine::ARN_SEGMENTS)) {
+            *slot = Some(segment).filter(|s| !s.is_empty());
+        }
+        let qualifier = split.next().filter(|s| !s.is_empty());
+        let trailing = split.next();
+
+        Ok(ResourceRef { source, segments, qualifier, trailing, _engine: PhantomData })
+    }
+
+    /// Returns the ARN this view borrows from.
+    pub fn as_str(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the segment at `index` (0 is the partition), or `None` if it is empty or absent.
+    pub fn segment(&self, index: usize) -> Option<&'a str> {
+        self.segments.get(index).copied().flatten()
+    }
+
+    /// Returns the qualifier following the typed segments, if any.
+    pub fn qualifier(&self) -> Option<&'a str> {
+        self.qualifier
+    }
+
+    /// Returns the segments after the qualifier, kept verbatim like
+    /// [`ResourceAbstract::trailing`].
+    pub fn trailing(&self) -> impl Iterator<Item = &'a str> + Clone {
+        self.trailing.into_iter().flat_map(|trailing| trailing.split(':'))
+    }
+
+    /// Parses the borrowed segments into an owned, typed [`ResourceAbstract`].
+    ///
+    /// # Errors
+    /// Returns `IamError::MalformedArn` if a segment fails to parse as its engine type.
+    pub fn to_resource(&self) -> Result<ResourceAbstract<Engine>, IamError> {
+        ResourceAbstract::from_str(self.source)
+    }
+}
+
+/// Catches swapped `matches` arguments in this crate's tests.
+///
+/// The pattern is always the receiver and the request the argument. A value containing an
+/// unescaped `*` or `?` checked against a pattern without any is almost certainly the two
+/// swapped: a literal pattern can then only match the wildcard text itself. Callers may still
+/// send wildcard requests unless `reject_wildcard_requests` is set, so the check only runs
+/// under `cfg(test)`, where every request is written by this crate.
+#[cfg(test)]
+pub(crate) fn assert_pattern_value(pattern: &str, value: &str) {
+    fn has_wildcard(s: &str) -> bool {
+        let mut escaped = false;
+        for c in s.chars() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '*' | '?' => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+    assert!(
+        !has_wildcard(value) || has_wildcard(pattern),
+        "wildcard value {value:?} matched against literal pattern {pattern:?}; are the arguments swapped?"
+    );
+}
+
+#[cfg(not(test))]
+#[inline(always)]
+pub(crate) fn assert_pattern_value(_pattern: &str, _value: &str) {}
+
+/// A glob pattern simple enough to match without a wildcard automaton: a literal, `prefix*`,
+/// or `prefix*suffix` with a single `*` and no `?` or escapes.
+#[derive(Debug, Clone)]
+pub(crate) enum FastGlob<S> {
+    Literal(S),
+    Prefix(S),
+    Affix(S, S),
+}
+
+impl<'p> FastGlob<&'p str> {
+    /// Returns the fast shape of `pattern`, or `None` if it needs the automaton.
+    pub(crate) fn parse(pattern: &'p str) -> Option<Self> {
+        if !pattern.contains(['*', '?', '\\']) {
+            return Some(FastGlob::Literal(pattern));
+        }
+        if let Some(prefix) = pattern.strip_suffix('*') {
+            if !prefix.contains(['*', '?', '\\']) {
+                return Some(FastGlob::Prefix(prefix));
+            }
+        }
+        if let Some((prefix, suffix)) = pattern.split_once('*') {
+            if !prefix.contains(['?', '\\']) && !suffix.contains(['*', '?', '\\']) {
+                return Some(FastGlob::Affix(prefix, suffix));
+            }
+        }
+        None
+    }
+
+    pub(crate) fn into_owned(self) -> FastGlob<String> {
+        match self {
+            FastGlob::Literal(literal) => FastGlob::Literal(literal.to_string()),
+            FastGlob::Prefix(prefix) => FastGlob::Prefix(prefix.to_string()),
+            FastGlob::Affix(prefix, suffix) => FastGlob::Affix(prefix.to_string(), suffix.to_string()),
+        }
+    }
+}
+
+impl<S: AsRef<str>> FastGlob<S> {
+    pub(crate) fn is_match(&self, value: &str) -> bool {
+        match self {
+            FastGlob::Literal(literal) => literal.as_ref() == value,
+            FastGlob::Prefix(prefix) => value.starts_with(prefix.as_ref()),
+            FastGlob::Affix(prefix, suffix) => {
+                let (prefix, suffix) = (prefix.as_ref(), suffix.as_ref());
+                value.len() >= prefix.len() + suffix.len() && value.starts_with(prefix) && value.ends_with(suffix)
+            }
+        }
+    }
+}
+
+/// Matches a single segment, skipping the automaton for the [`FastGlob`] shapes.
+pub(crate) fn glob(pattern: &str, value: &str) -> Result<bool, &'static str> {
+    if let Some(fast) = FastGlob::parse(pattern) {
+        return Ok(fast.is_match(value));
+    }
+    let pattern = Wildcard::new(pattern.as_bytes()).map_err(|_| "Failed to compile wildcard pattern")?;
+    Ok(pattern.is_match(value.as_bytes()))
+}
+
+/// A raw segment matched with [`glob`], so optional segments can go through the `Option`
+/// impl of [`MatchesTrait`].
+pub(crate) struct Glob<'a>(pub(crate) &'a str);
+
+impl MatchesTrait<bool> for Glob<'_> {
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        assert_pattern_value(self.0, value.0);
+        glob(self.0, value.0)
+    }
+}
+
+/// Trailing segments, only compared when both sides have some, and then matched position by
+/// position against a value with the same number of segments.
+pub(crate) struct GlobList<I>(pub(crate) I);
+
+impl<I> MatchesTrait<bool> for GlobList<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: AsRef<str>,
+{
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        let (len, value_len) = (self.0.clone().into_iter().count(), value.0.clone().into_iter().count());
+        if len == 0 || value_len == 0 {
+            return Ok(true);
+        }
+        if len != value_len {
+            return Ok(false);
+        }
+        for (l, r) in self.0.clone().into_iter().zip(value.0.clone()) {
+            if !Glob(l.as_ref()).matches(&Glob(r.as_ref()))? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<Engine: EngineTrait> MatchesTrait<bool> for ResourceRef<'_, Engine> {
+    fn matches(&self, other: &Self) -> Result<bool, &'static str> {
+        for (l, r) in self.segments.iter().zip(other.segments.iter()) {
+            if !l.map(Glob).matches(&r.map(Glob))? {
+                return Ok(false);
+            }
+        }
+        Ok(self.qualifier.map(Glob).matches(&other.qualifier.map(Glob))?
+            && GlobList(self.trailing()).matches(&GlobList(other.trailing()))?)
+    }
+}
+
+impl<Engine: EngineTrait> Clone for ResourceRef<'_, Engine> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Engine: EngineTrait> Copy for ResourceRef<'_, Engine> {}
+
+impl<Engine: EngineTrait> fmt::Debug for ResourceRef<'_, Engine> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ResourceRef").field(&self.source).finish()
+    }
+}
+
+impl<Engine: EngineTrait> fmt::Display for ResourceRef<'_, Engine> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::{AwsEngine, WildString};
+
+    #[test]
+    fn test_ref_matching_agrees_with_owned_matching() {
+        let patterns = [
+            "arn:aws:s3:::bucket/*",
+            "arn:aws:s3:::bucket/logs/*.log",
+            "arn:aws:s3:::*-logs-*",
+            "arn:aws:ec2:us-east-1:123456789012:instance:i-?bc",
+            "arn:aws:ec2::*:instance:*",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod*",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod:a",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod:*",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod:a:*",
+            "arn:aws-us-gov:s3:::bucket/key",
+            "arn:::::",
+            "*",
+        ];
+        let requests = [
+            "arn:aws:s3:::bucket/key",
+            "arn:aws:s3:::bucket/logs/app.log",
+            "arn:aws:s3:::other/key",
+            "arn:aws:s3:::app-logs-2024",
+            "arn:aws:ec2:us-east-1:123456789012:instance:i-abc",
+            "arn:aws:ec2:eu-west-1:999999999999:instance:i-xyz",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod-2",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:dev",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod:a",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod:b",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn:prod:a:b",
+            "arn:aws:lambda:us-west-2:123456789012:function:my-fn::a",
+            "arn:aws-us-gov:s3:::bucket/key",
+        ];
+        for pattern in patterns {
+            let owned_pattern = ResourceAbstract::<AwsEngine>::from_str(pattern).unwrap();
+            let ref_pattern = ResourceRef::<AwsEngine>::parse(pattern).unwrap();
+            for request in requests {
+                let owned = owned_pattern.matches(&ResourceAbstract::from_str(request).unwrap());
+                let borrowed = ref_pattern.matches(&ResourceRef::parse(request).unwrap());
+                assert_eq!(borrowed, owned, "pattern {pattern} request {request}");
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "are the arguments swapped?")]
+    fn test_swapped_arguments_trip_assertion() {
+        let pattern = ResourceRef::<AwsEngine>::parse("arn:aws:s3:::bucket/*").unwrap();
+        let request = ResourceRef::<AwsEngine>::parse("arn:aws:s3:::bucket/key").unwrap();
+        let _ = request.matches(&pattern);
+    }
+
+    #[test]
+    #[should_panic(expected = "are the arguments swapped?")]
+    fn test_swapped_segment_arguments_trip_assertion() {
+        let _ = WildString("s3:GetObject".to_string()).matches(&WildString("s3:Get*".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "are the arguments swapped?")]
+    fn test_swapped_action_arguments_trip_assertion() {
+        let _ = AwsEngine::action_matches(&WildString("s3:GetObject".to_string()), &WildString("s3:*".to_string()));
+    }
+
+    #[test]
+    fn test_assert_pattern_value_accepts_pattern_pairs() {
+        assert_pattern_value("bucket/*", "bucket/key");
+        assert_pattern_value("bucket/*", "bucket/*");
+        assert_pattern_value("bucket/key", "bucket/key");
+        assert_pattern_value("bucket/key\\*", "bucket/key\\*");
+    }
+
+    #[test]
+    fn test_parse_borrows_segments() {
+        let arn = "arn:aws:lambda:us-east-1:123456789012:function:my-fn:prod";
+        let resource = ResourceRef::<AwsEngine>::parse(arn).unwrap();
+        assert_eq!(resource.segment(1), Some("lambda"));
+        assert_eq!(resource.segment(5), Some("my-fn"));
+        assert_eq!(resource.qualifier(), Some("prod"));
+        assert_eq!(resource.trailing().count(), 0);
+        let extended = ResourceRef::<AwsEngine>::parse("arn:aws:lambda:us-east-1:123456789012:function:my-fn:prod:a::b").unwrap();
+        assert_eq!(extended.trailing().collect::<Vec<_>>(), ["a", "", "b"]);
+        assert_eq!(resource.as_str(), arn);
+        assert_eq!(ResourceRef::<AwsEngine>::parse("arn:aws:s3:::bucket").unwrap().segment(2), None);
+        assert!(ResourceRef::<AwsEngine>::parse("bucket").is_err());
+    }
+
+    #[test]
+    fn test_to_resource_reports_typed_errors() {
+        let resource = ResourceRef::<AwsEngine>::parse("arn:aws:ec2:zz:123456789012:instance:i-1").unwrap();
+        assert!(matches!(resource.to_resource(), Err(IamError::MalformedArn { segment: 3, .. })));
+
+        let resource = ResourceRef::<AwsEngine>::parse("arn:aws:s3:::bucket").unwrap();
+        let owned = resource.to_resource().unwrap();
+        assert_eq!(owned, ResourceAbstract::from_str("arn:aws:s3:::bucket").unwrap());
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::{Arc, RwLock};
+
+use crate::engine::EngineTrait;
+use crate::{PolicyCollection, ResourceAbstract};
+
+/// A cloneable handle to a `PolicyCollection` that can be swapped while it is being read.
+///
+/// This encodes the usual reload pattern for long-running services: request handlers call
+/// [`SharedPolicies::validate`], which takes a read lock, while a background task calls
+/// [`SharedPolicies::replace`] or [`SharedPolicies::update`], which take the write lock.
+/// Every clone refers to the same collection, so a reload is seen by all holders.
+///
+/// A reader never observes a partially applied update: each `validate` call sees the
+/// collection either entirely before or entirely after a write.
+///
+/// # Type Parameters
+/// - `Engine`: A type implementing the `EngineTrait`, which defines the core types and behaviors
+///   used by the wrapped policies.
+#[derive(Debug, Clone)]
+pub struct SharedPolicies<Engine: EngineTrait> {
+    inner: Arc<RwLock<PolicyCollection<Engine>>>,
+}
+
+impl<Engine: EngineTrait> SharedPolicies<Engine> {
+    /// Wraps `policies` in a new shared handle.
+    pub fn new(policies: PolicyCollection<Engine>) -> Self {
+        SharedPolicies { inner: Arc::new(RwLock::new(policies)) }
+    }
+
+    /// Validates whether the given action is allowed on the specified resource under a read lock.
+    ///
+    /// See [`PolicyCollection::validate`] for the evaluation rules.
+    pub fn validate(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> bool {
+        self.read(|policies| policies.validate(action, resource))
+    }
+
+    /// Runs `f` against the current collection under a read lock.
+    ///
+    /// Use this for evaluations other than [`SharedPolicies::validate`], or to make several
+    /// checks against the same snapshot.
+    pub fn read<R, F: FnOnce(&PolicyCollection<Engine>) -> R>(&self, f: F) -> R {
+        f(&self.inner.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Applies `f` to the collection under the write lock.
+    pub fn update<F: FnOnce(&mut PolicyCollection<Engine>)>(&self, f: F) {
+        f(&mut self.inner.write().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// Replaces the collection, returning the previous one.
+    pub fn replace(&self, policies: PolicyCollection<Engine>) -> PolicyCollection<Engine> {
+        std::mem::replace(&mut self.inner.write().unwrap_or_else(|e| e.into_inner()), policies)
+    }
+}
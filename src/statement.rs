@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use crate::{Effect, EngineTrait, ResourceAbstract};
+use crate::{Effect, EngineTrait, IamError, MatchErrorMode, PreparedStatement, ResourceAbstract};
 use crate::traits::MatchesTrait;
 
 /// Represents a statement in an IAM policy, defining access control rules for actions and resources.
@@ -18,8 +18,13 @@ use crate::traits::MatchesTrait;
 /// - `effect`: Specifies whether the actions in this statement are allowed or denied.
 /// - `actions`: A list of actions (e.g., `read`, `write`) to which this statement applies.
 /// - `resources`: A list of resources (e.g., a specific bucket or instance) to which this statement applies.
+/// - `except_resources`: Resources carved out of `resources`; the statement never applies to them.
+///
+/// When deserializing, an absent `resources` field is read as `["*"]`, matching every resource.
+/// This deviates from AWS, which requires `Resource`, to accept minimal policy formats that
+/// omit it. An explicit empty list still matches nothing.
 /// ```
-#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Statement<Engine: EngineTrait> {
     /// Specifies whether the statement allows or denies the actions on the resources.
     pub effect: Effect,
@@ -29,6 +34,12 @@ pub struct Statement<Engine: EngineTrait> {
 
     /// The list of resources that this statement applies to.
     pub resources: Vec<ResourceAbstract<Engine>>,
+
+    /// Resources excluded from this statement even when they match `resources`.
+    ///
+    /// This allows a statement to cover "this bucket, but not these sub-paths" without
+    /// inverting the whole statement. It is optional in the serialized form.
+    pub except_resources: Vec<ResourceAbstract<Engine>>,
 }
 #[cfg(feature = "with-sqlx")]
 use sqlx::postgres::PgHasArrayType;
@@ -72,14 +83,40 @@ where
 }
 
 
-use serde::de::{Deserializer, Error, MapAccess, StdError, Visitor};
+use serde::de::{Deserializer, Error, MapAccess, SeqAccess, Visitor};
+#[cfg(feature = "with-sqlx")]
+use serde::de::StdError;
+use serde::ser::{SerializeStruct, Serializer};
 use std::fmt;
 
+impl<Engine: EngineTrait> Serialize for Statement<Engine> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Human-readable formats omit empty `except_resources`; binary formats are positional
+        // and always need every field.
+        let skip_except = serializer.is_human_readable() && self.except_resources.is_empty();
+        let mut state = serializer.serialize_struct("Statement", if skip_except { 3 } else { 4 })?;
+        state.serialize_field("effect", &self.effect)?;
+        state.serialize_field("actions", &self.actions)?;
+        state.serialize_field("resources", &self.resources)?;
+        if skip_except {
+            state.skip_field("except_resources")?;
+        } else {
+            state.serialize_field("except_resources", &self.except_resources)?;
+        }
+        state.end()
+    }
+}
+
 impl<'de, Engine: EngineTrait> Deserialize<'de> for Statement<Engine> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
+        const FIELDS: &[&str] = &["effect", "actions", "resources", "except_resources"];
+
         struct StatementVisitor<Engine: EngineTrait>(std::marker::PhantomData<Engine>);
 
         impl<'de, Engine: EngineTrait> Visitor<'de> for StatementVisitor<Engine> {
@@ -89,6 +126,19 @@ impl<'de, Engine: EngineTrait> Deserialize<'de> for Statement<Engine> {
                 formatter.write_str("a valid Statement object with effect, actions, and resources")
             }
 
+            /// Accepts the positional form produced by non-self-describing formats.
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                Ok(Statement {
+                    effect: seq.next_element()?.ok_or_else(|| Error::invalid_length(0, &self))?,
+                    actions: seq.next_element()?.ok_or_else(|| Error::invalid_length(1, &self))?,
+                    resources: seq.next_element()?.ok_or_else(|| Error::invalid_length(2, &self))?,
+                    except_resources: seq.next_element()?.unwrap_or_default(),
+                })
+            }
+
             fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
             where
                 M: MapAccess<'de>,
@@ -96,27 +146,50 @@ impl<'de, Engine: EngineTrait> Deserialize<'de> for Statement<Engine> {
                 let mut effect = None;
                 let mut actions = None;
                 let mut resources = None;
+                let mut except_resources = None;
 
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
-                        "effect" => effect = Some(map.next_value()?),
-                        "actions" => actions = Some(map.next_value()?),
-                        "resources" => resources = Some(map.next_value()?),
-                        _ => return Err(Error::unknown_field(&key, &["effect", "actions", "resources"])),
+                        "effect" => {
+                            if effect.is_some() {
+                                return Err(Error::duplicate_field("effect"));
+                            }
+                            effect = Some(map.next_value()?)
+                        }
+                        "actions" => {
+                            if actions.is_some() {
+                                return Err(Error::duplicate_field("actions"));
+                            }
+                            actions = Some(map.next_value()?)
+                        }
+                        "resources" => {
+                            if resources.is_some() {
+                                return Err(Error::duplicate_field("resources"));
+                            }
+                            resources = Some(map.next_value()?)
+                        }
+                        "except_resources" => {
+                            if except_resources.is_some() {
+                                return Err(Error::duplicate_field("except_resources"));
+                            }
+                            except_resources = Some(map.next_value()?)
+                        }
+                        _ => return Err(Error::unknown_field(&key, FIELDS)),
                     }
                 }
 
                 Ok(Statement {
                     effect: effect.ok_or_else(|| Error::missing_field("effect"))?,
                     actions: actions.ok_or_else(|| Error::missing_field("actions"))?,
-                    resources: resources.ok_or_else(|| Error::missing_field("resources"))?,
+                    resources: resources.unwrap_or_else(|| vec![ResourceAbstract::any()]),
+                    except_resources: except_resources.unwrap_or_default(),
                 })
             }
         }
 
         deserializer.deserialize_struct(
             "Statement",
-            &["effect", "actions", "resources"],
+            FIELDS,
             StatementVisitor(std::marker::PhantomData),
         )
     }
@@ -139,7 +212,7 @@ impl<'de, Engine: EngineTrait> Deserialize<'de> for Statement<Engine> {
 /// let effect = MaybeEffect::Allow;
 /// assert_eq!(effect, MaybeEffect::Allow);
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MaybeEffect {
     /// Explicitly allows access.
     Allow,
@@ -151,6 +224,89 @@ pub enum MaybeEffect {
     NotSpecified,
 }
 
+/// Describes how much broader a granting pattern is than the request it matched.
+///
+/// Variants are ordered from narrowest to broadest, so the broadest of several
+/// classifications can be taken with `max`.
+///
+/// # Variants
+/// - `Exact`: The pattern is a literal that names exactly the requested value.
+/// - `Narrow`: The pattern contains wildcards (or leaves ARN segments unset) but is not
+///   unrestricted, e.g. `s3:Get*` or `arn:aws:s3:::bucket/*`.
+/// - `Full`: The pattern matches everything, e.g. `*` or a resource whose segments are all
+///   `*` or unset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Breadth {
+    /// The grant names exactly the requested value.
+    Exact,
+
+    /// The grant uses wildcards but is restricted.
+    Narrow,
+
+    /// The grant matches everything.
+    Full,
+}
+
+impl Breadth {
+    fn of_pattern(pattern: &str) -> Breadth {
+        if pattern == "*" {
+            Breadth::Full
+        } else if pattern.contains(['*', '?']) {
+            Breadth::Narrow
+        } else {
+            Breadth::Exact
+        }
+    }
+
+    fn of_resource<Engine: EngineTrait>(resource: &ResourceAbstract<Engine>) -> Breadth {
+        let segments = resource.segment_strings().map(|s| s.map_or(Breadth::Full, |s| Breadth::of_pattern(&s)));
+        if segments.iter().all(|b| *b == Breadth::Full) {
+            Breadth::Full
+        } else if segments.iter().any(|b| *b != Breadth::Exact) {
+            Breadth::Narrow
+        } else {
+            Breadth::Exact
+        }
+    }
+}
+
+/// Whether a pattern matched a request literally or through wildcards.
+///
+/// `Exact` orders before `Wildcard`, so the most specific of several matches is their `min`.
+/// This supports "most specific statement wins" precedence on top of the usual evaluation.
+///
+/// # Variants
+/// - `Exact`: The pattern has no wildcards and names exactly the requested value.
+/// - `Wildcard`: The pattern matched through `*`, `?` or unset segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// The pattern is the requested value.
+    Exact,
+
+    /// The pattern matched through wildcards.
+    Wildcard,
+}
+
+impl MatchKind {
+    /// Classifies a pattern already known to match `value`.
+    pub(crate) fn of_match(pattern: &str, value: &str) -> MatchKind {
+        if pattern == value && !pattern.contains(['*', '?']) {
+            MatchKind::Exact
+        } else {
+            MatchKind::Wildcard
+        }
+    }
+}
+
+/// The text an action is compared on, lowercased for case-insensitive engines.
+fn action_text<Engine: EngineTrait>(action: &Engine::Action) -> String {
+    if Engine::CASE_INSENSITIVE_ACTIONS {
+        action.to_string().to_lowercase()
+    } else {
+        action.to_string()
+    }
+}
+
 impl<Engine: EngineTrait> Statement<Engine> {
     /// Checks whether the given `action` and `resource` match this statement.
     ///
@@ -161,7 +317,11 @@ impl<Engine: EngineTrait> Statement<Engine> {
     ///    the method returns `MaybeEffect::Deny`.
     /// 2. If the `resource` and `action` both match, and the effect is `Allow`,
     ///    the method sets `is_allow` to `true` but continues evaluating other resources/actions.
-    /// 3. If no matches are found, the method returns `MaybeEffect::NotSpecified`.
+    /// 3. If no matches are found, or the resource matches one of `except_resources`, the
+    ///    method returns `MaybeEffect::NotSpecified`.
+    ///
+    /// A pattern that cannot be matched is resolved with [`MatchErrorMode::FailClosed`]: the
+    /// statement counts as matching if it is a `Deny` and as not matching if it is an `Allow`.
     ///
     /// # Parameters
     /// - `action`: The action to evaluate against the statement.
@@ -177,24 +337,449 @@ impl<Engine: EngineTrait> Statement<Engine> {
         action: &Engine::Action,
         resource: &ResourceAbstract<Engine>,
     ) -> MaybeEffect {
+        self.matches_with_errors(action, resource, MatchErrorMode::FailClosed)
+            .unwrap_or(MaybeEffect::NotSpecified)
+    }
+
+    /// Checks whether the given `action` and `resource` match this statement, resolving
+    /// matching errors according to `mode`.
+    ///
+    /// # Parameters
+    /// - `action`: The action to evaluate against the statement.
+    /// - `resource`: The resource to evaluate against the statement.
+    /// - `mode`: What an errored statement evaluates to.
+    ///
+    /// # Returns
+    /// - `Ok(MaybeEffect)` with the statement's effect, or the effect chosen by `mode` if a
+    ///   pattern could not be evaluated.
+    /// - `Err(IamError::Match)` only when `mode` is [`MatchErrorMode::Propagate`].
+    pub fn matches_with_errors(
+        &self,
+        action: &Engine::Action,
+        resource: &ResourceAbstract<Engine>,
+        mode: MatchErrorMode,
+    ) -> Result<MaybeEffect, IamError> {
+        match self.try_matches(action, resource) {
+            Ok(effect) => Ok(effect),
+            Err(err) => match (mode, &self.effect) {
+                (MatchErrorMode::Propagate, _) => Err(err),
+                (MatchErrorMode::FailClosed, Effect::Deny) => Ok(MaybeEffect::Deny),
+                (MatchErrorMode::FailOpen, Effect::Allow) => Ok(MaybeEffect::Allow),
+                _ => Ok(MaybeEffect::NotSpecified),
+            },
+        }
+    }
+
+    /// Compiles this statement's wildcard patterns once for repeated evaluation.
+    ///
+    /// The returned [`PreparedStatement`] gives the same results as this statement, without
+    /// recompiling a pattern on every match. The statement is cloned into it.
+    pub fn prepare(&self) -> PreparedStatement<Engine> {
+        PreparedStatement::new(self)
+    }
+
+    /// Returns `true` if the statement applies to `resource` for at least one of its actions,
+    /// i.e. some resource pattern matches and no except-resource pattern does.
+    pub(crate) fn applies_to_resource(&self, resource: &ResourceAbstract<Engine>) -> bool {
+        !self.except_resources.iter().any(|r| matches!(r.matches(resource), Ok(true)))
+            && self.resources.iter().any(|r| matches!(r.matches(resource), Ok(true)))
+    }
+
+    /// Reports action/resource pairs whose service prefixes disagree.
+    ///
+    /// An action such as `s3:GetObject` only ever applies to resources of the `s3` service, so
+    /// pairing it with an `arn:aws:ec2:...` resource is almost certainly a mistake. The action's
+    /// service is the text before its first `:`, compared case-insensitively with the resource's
+    /// service segment. Pairs are skipped when either service contains a wildcard (e.g. `*:*`
+    /// covers every service), when the action has no service prefix (e.g. `*`), or when the
+    /// resource leaves the service unset.
+    ///
+    /// # Returns
+    /// The `(action index, resource index)` pairs that mismatch, ordered by action and then by
+    /// resource. An empty vector means no mismatch was found.
+    pub fn service_mismatches(&self) -> Vec<(usize, usize)> {
+        let concrete = |service: &str| !service.contains(['*', '?']);
+        let mut mismatches = Vec::new();
+        for (a, action) in self.actions.iter().enumerate() {
+            let action = action.to_string();
+            let Some((action_service, _)) = action.split_once(':').filter(|(s, _)| concrete(s)) else {
+                continue;
+            };
+            for (r, resource) in self.resources.iter().enumerate() {
+                let Some(resource_service) = resource.service.as_ref().map(ToString::to_string) else {
+                    continue;
+                };
+                if concrete(&resource_service) && !action_service.eq_ignore_ascii_case(&resource_service) {
+                    mismatches.push((a, r));
+                }
+            }
+        }
+        mismatches
+    }
+
+    /// Reports actions already covered by another action in the same statement.
+    ///
+    /// An action is redundant if another entry's pattern matches its text, e.g. `s3:GetObject`
+    /// and `s3:Get*` next to `s3:*`. Of two identical entries only the later one is reported,
+    /// so removing every reported index keeps the statement's meaning. Patterns that fail to
+    /// evaluate are never treated as covering anything.
+    ///
+    /// # Returns
+    /// The indices of redundant actions in ascending order.
+    pub fn redundant_actions(&self) -> Vec<usize> {
+        // A pattern without wildcards never covers one with them, and is not matched against it.
+        let literal = |a: &Engine::Action| !a.to_string().contains(['*', '?']);
+        let covers = |a: &Engine::Action, b: &Engine::Action| {
+            (!literal(a) || literal(b)) && matches!(Engine::action_matches(a, b), Ok(true))
+        };
+        (0..self.actions.len())
+            .filter(|&i| {
+                let action = &self.actions[i];
+                self.actions.iter().enumerate().any(|(j, other)| {
+                    j != i && covers(other, action) && (!covers(action, other) || j < i)
+                })
+            })
+            .collect()
+    }
+
+    /// Classifies how much broader this statement's grant is than the given request.
+    ///
+    /// Every action and resource pattern of an `Allow` statement that matches the request is
+    /// classified with [`Breadth`], and the broadest classification is returned. An `Exact`
+    /// result means the statement grants precisely this request, while `Narrow` and `Full`
+    /// indicate that the statement confers additional access, which security tooling can
+    /// flag as a least-privilege violation.
+    ///
+    /// # Returns
+    /// - `Some(Breadth)` if this is an `Allow` statement matching the request.
+    /// - `None` if the statement does not grant the request.
+    pub fn breadth_over(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Option<Breadth> {
+        if self.effect != Effect::Allow || self.matches(action, resource) != MaybeEffect::Allow {
+            return None;
+        }
+        let resource_breadth = self
+            .resources
+            .iter()
+            .filter(|r| matches!(r.matches(resource), Ok(true)))
+            .map(Breadth::of_resource)
+            .max()?;
+        let action_breadth = self
+            .actions
+            .iter()
+            .filter(|a| matches!(Engine::action_matches(a, action), Ok(true)))
+            .map(|a| Breadth::of_pattern(&a.to_string()))
+            .max()?;
+        Some(resource_breadth.max(action_breadth))
+    }
+
+    /// Reports how specifically this statement applies to the request.
+    ///
+    /// Among the action patterns and resource patterns matching the request, the most specific
+    /// of each is taken; the statement is an `Exact` match only if both are exact. A `Deny`
+    /// that applies only because a pattern failed to evaluate counts as `Wildcard`.
+    ///
+    /// # Parameters
+    /// - `action`: The action to evaluate against the statement.
+    /// - `resource`: The resource to evaluate against the statement.
+    ///
+    /// # Returns
+    /// - `Some(MatchKind)` if [`Statement::matches`] would return `Allow` or `Deny`.
+    /// - `None` if the statement does not apply.
+    pub fn match_kind(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Option<MatchKind> {
+        if self.matches(action, resource) == MaybeEffect::NotSpecified {
+            return None;
+        }
+        let action_string = action_text::<Engine>(action);
+        let action_kind = self
+            .actions
+            .iter()
+            .filter(|a| matches!(Engine::action_matches(a, action), Ok(true)))
+            .map(|a| MatchKind::of_match(&action_text::<Engine>(a), &action_string))
+            .min();
+        let resource_kind = self
+            .resources
+            .iter()
+            .filter_map(|r| r.match_kind(resource).ok().flatten())
+            .min();
+        match (action_kind, resource_kind) {
+            (Some(MatchKind::Exact), Some(MatchKind::Exact)) => Some(MatchKind::Exact),
+            _ => Some(MatchKind::Wildcard),
+        }
+    }
+
+    /// Scores how specifically this statement applies to the request.
+    ///
+    /// The score is the number of exactly matched segments: one for a literal action equal to
+    /// `action`, plus the ARN segments named literally by the most specific matching resource.
+    /// Used by [`EvaluationMode::MostSpecificWins`](crate::EvaluationMode::MostSpecificWins).
+    ///
+    /// # Returns
+    /// - `Some(score)` if [`Statement::matches`] would return `Allow` or `Deny`.
+    /// - `None` if the statement does not apply.
+    pub fn specificity(&self, action: &Engine::Action, resource: &ResourceAbstract<Engine>) -> Option<usize> {
+        if self.matches(action, resource) == MaybeEffect::NotSpecified {
+            return None;
+        }
+        let action_string = action_text::<Engine>(action);
+        let exact_action = self
+            .actions
+            .iter()
+            .any(|a| matches!(Engine::action_matches(a, action), Ok(true)) && MatchKind::of_match(&action_text::<Engine>(a), &action_string) == MatchKind::Exact);
+        let resource_score = self
+            .resources
+            .iter()
+            .filter(|r| matches!(r.matches(resource), Ok(true)))
+            .map(|r| r.exact_segments(resource))
+            .max()
+            .unwrap_or(0);
+        Some(usize::from(exact_action) + resource_score)
+    }
+
+    /// Checks whether the given `action` and `resource` match this statement, propagating
+    /// matching errors.
+    ///
+    /// This follows the same rules as [`Statement::matches`], but a pattern that fails to
+    /// compile (e.g. a malformed wildcard) yields an `Err` instead of being treated as a
+    /// non-match. Use it when a broken policy must be distinguished from a denied request.
+    ///
+    /// # Returns
+    /// - `Ok(MaybeEffect)` with the same value `matches` would return for a valid statement.
+    /// - `Err(IamError::Match)` if any action or resource pattern could not be evaluated.
+    pub fn try_matches(
+        &self,
+        action: &Engine::Action,
+        resource: &ResourceAbstract<Engine>,
+    ) -> Result<MaybeEffect, IamError> {
+        for r in self.except_resources.iter() {
+            if r.matches(resource)? {
+                return Ok(MaybeEffect::NotSpecified);
+            }
+        }
+        // An empty `resources` or `actions` list matches nothing, so the statement stays silent
         let mut is_allow = false;
         for r in self.resources.iter() {
-            if let Ok(true) = r.matches(resource) {
+            if r.matches(resource)? {
                 for a in self.actions.iter() {
-                    if let Ok(true) = a.matches(action) {
+                    if Engine::action_matches(a, action)? {
                         if self.effect == Effect::Deny {
-                            return MaybeEffect::Deny;
-                        } else if self.effect == Effect::Allow {
-                            is_allow = true;
+                            return Ok(MaybeEffect::Deny);
                         }
+                        is_allow = true;
                     }
                 }
             }
         }
         if is_allow {
-            MaybeEffect::Allow
+            Ok(MaybeEffect::Allow)
         } else {
-            MaybeEffect::NotSpecified
+            Ok(MaybeEffect::NotSpecified)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::{AwsEngine, WildString};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_duplicate_key_is_rejected() {
+        let json = r#"{"effect": "allow", "actions": ["s3:GetObject"], "actions": ["s3:*"], "resources": []}"#;
+        let err = serde_json::from_str::<Statement<AwsEngine>>(json).unwrap_err();
+        assert!(err.to_string().contains("duplicate field `actions`"), "{err}");
+    }
+
+    #[test]
+    fn test_absent_resources_match_everything() {
+        let statement: Statement<AwsEngine> =
+            serde_json::from_str(r#"{"effect": "allow", "actions": ["s3:GetObject"]}"#).unwrap();
+        assert_eq!(statement.resources, vec![ResourceAbstract::any()]);
+        let action = WildString("s3:GetObject".to_string());
+        let object = ResourceAbstract::from_str("arn:aws:s3:::my-bucket/key").unwrap();
+        assert_eq!(statement.matches(&action, &object), MaybeEffect::Allow);
+
+        let err = serde_json::from_str::<Statement<AwsEngine>>(r#"{"effect": "allow"}"#).unwrap_err();
+        assert!(err.to_string().contains("missing field `actions`"), "{err}");
+    }
+
+    #[test]
+    fn test_except_resources_carve_out_a_prefix() {
+        let statement: Statement<AwsEngine> = serde_json::from_str(r#"{
+            "effect": "allow",
+            "actions": ["s3:GetObject"],
+            "resources": ["arn:aws:s3:::my-bucket/*"],
+            "except_resources": ["arn:aws:s3:::my-bucket/secret/*"]
+        }"#).unwrap();
+        let action = WildString("s3:GetObject".to_string());
+
+        let public = ResourceAbstract::from_str("arn:aws:s3:::my-bucket/reports/q1.csv").unwrap();
+        let secret = ResourceAbstract::from_str("arn:aws:s3:::my-bucket/secret/keys.txt").unwrap();
+        assert_eq!(statement.matches(&action, &public), MaybeEffect::Allow);
+        assert_eq!(statement.matches(&action, &secret), MaybeEffect::NotSpecified);
+    }
+
+    #[test]
+    fn test_except_resources_is_optional() {
+        let json = r#"{"effect":"deny","actions":["s3:*"],"resources":["arn:aws:s3:::my-bucket"]}"#;
+        let statement: Statement<AwsEngine> = serde_json::from_str(json).unwrap();
+        assert!(statement.except_resources.is_empty());
+        assert!(!serde_json::to_string(&statement).unwrap().contains("except_resources"));
+    }
+
+    #[test]
+    fn test_try_matches_propagates_invalid_pattern() {
+        let statement: Statement<AwsEngine> = serde_json::from_str(
+            r#"{"effect": "allow", "actions": ["s3:Get\\"], "resources": ["arn:aws:s3:::my-bucket"]}"#,
+        ).unwrap();
+        let resource = ResourceAbstract::from_str("arn:aws:s3:::my-bucket").unwrap();
+        let action = WildString("s3:GetObject".to_string());
+
+        assert_eq!(statement.matches(&action, &resource), MaybeEffect::NotSpecified);
+        assert!(matches!(statement.try_matches(&action, &resource), Err(IamError::Match(_))));
+    }
+
+    #[test]
+    fn test_empty_actions_or_resources_match_nothing() {
+        let resource = ResourceAbstract::from_str("arn:aws:s3:::my-bucket").unwrap();
+        let action = WildString("s3:GetObject".to_string());
+        for (actions, resources) in [(r#"[]"#, r#"["*"]"#), (r#"["*"]"#, r#"[]"#), (r#"[]"#, r#"[]"#)] {
+            let empty = statement(actions, resources);
+            assert_eq!(empty.matches(&action, &resource), MaybeEffect::NotSpecified, "{actions} {resources}");
+            assert_eq!(empty.try_matches(&action, &resource), Ok(MaybeEffect::NotSpecified));
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_match_error_modes() {
+        let resource = ResourceAbstract::from_str("arn:aws:s3:::my-bucket").unwrap();
+        let action = WildString("s3:GetObject".to_string());
+        let broken = |effect: &str| -> Statement<AwsEngine> {
+            serde_json::from_str(&format!(
+                r#"{{"effect": "{effect}", "actions": ["s3:Get\\"], "resources": ["arn:aws:s3:::my-bucket"]}}"#
+            )).unwrap()
+        };
+        let (allow, deny) = (broken("allow"), broken("deny"));
+
+        assert_eq!(allow.matches_with_errors(&action, &resource, MatchErrorMode::FailClosed), Ok(MaybeEffect::NotSpecified));
+        assert_eq!(deny.matches_with_errors(&action, &resource, MatchErrorMode::FailClosed), Ok(MaybeEffect::Deny));
+        assert_eq!(allow.matches_with_errors(&action, &resource, MatchErrorMode::FailOpen), Ok(MaybeEffect::Allow));
+        assert_eq!(deny.matches_with_errors(&action, &resource, MatchErrorMode::FailOpen), Ok(MaybeEffect::NotSpecified));
+        assert!(matches!(allow.matches_with_errors(&action, &resource, MatchErrorMode::Propagate), Err(IamError::Match(_))));
+        assert!(matches!(deny.matches_with_errors(&action, &resource, MatchErrorMode::Propagate), Err(IamError::Match(_))));
+        assert_eq!(deny.matches(&action, &resource), MaybeEffect::Deny);
+    }
+
+    fn statement(actions: &str, resources: &str) -> Statement<AwsEngine> {
+        serde_json::from_str(&format!(r#"{{"effect": "allow", "actions": {actions}, "resources": {resources}}}"#)).unwrap()
+    }
+
+    #[test]
+    fn test_breadth_over_classifies_grants() {
+        let action = WildString("s3:GetObject".to_string());
+        let resource = ResourceAbstract::from_str("arn:aws:s3:us-east-1:123456789012:bucket:reports").unwrap();
+
+        let exact = statement(r#"["s3:GetObject"]"#, r#"["arn:aws:s3:us-east-1:123456789012:bucket:reports"]"#);
+        assert_eq!(exact.breadth_over(&action, &resource), Some(Breadth::Exact));
+
+        let narrow = statement(r#"["s3:Get*"]"#, r#"["arn:aws:s3:us-east-1:123456789012:bucket:reports"]"#);
+        assert_eq!(narrow.breadth_over(&action, &resource), Some(Breadth::Narrow));
+
+        let full = statement(r#"["*"]"#, r#"["arn:aws:s3:us-east-1:123456789012:bucket:reports"]"#);
+        assert_eq!(full.breadth_over(&action, &resource), Some(Breadth::Full));
+
+        let full_resource = statement(r#"["s3:GetObject"]"#, r#"["arn::*::*:*:*"]"#);
+        assert_eq!(full_resource.breadth_over(&action, &resource), Some(Breadth::Full));
+    }
+
+    #[test]
+    fn test_match_kind_distinguishes_exact_from_wildcard() {
+        let action = WildString("s3:GetObject".to_string());
+        let resource = ResourceAbstract::from_str("arn:aws:s3:::reports").unwrap();
+
+        let exact = statement(r#"["s3:GetObject"]"#, r#"["arn:aws:s3:::reports"]"#);
+        assert_eq!(exact.match_kind(&action, &resource), Some(MatchKind::Exact));
+
+        let any_action = statement(r#"["*"]"#, r#"["arn:aws:s3:::reports"]"#);
+        assert_eq!(any_action.match_kind(&action, &resource), Some(MatchKind::Wildcard));
+
+        let any_resource = statement(r#"["s3:GetObject"]"#, r#"["*"]"#);
+        assert_eq!(any_resource.match_kind(&action, &resource), Some(MatchKind::Wildcard));
+
+        let both = statement(r#"["s3:*", "s3:GetObject"]"#, r#"["arn:aws:s3:::rep*", "arn:aws:s3:::reports"]"#);
+        assert_eq!(both.match_kind(&action, &resource), Some(MatchKind::Exact));
+
+        let other = statement(r#"["s3:PutObject"]"#, r#"["arn:aws:s3:::reports"]"#);
+        assert_eq!(other.match_kind(&action, &resource), None);
+    }
+
+    #[test]
+    fn test_breadth_over_ignores_non_granting_statements() {
+        let action = WildString("s3:GetObject".to_string());
+        let resource = ResourceAbstract::from_str("arn:aws:s3:::reports").unwrap();
+        let other = statement(r#"["s3:PutObject"]"#, r#"["arn:aws:s3:::reports"]"#);
+        assert_eq!(other.breadth_over(&action, &resource), None);
+
+        let mut deny = statement(r#"["*"]"#, r#"["arn:aws:s3:::reports"]"#);
+        deny.effect = Effect::Deny;
+        assert_eq!(deny.breadth_over(&action, &resource), None);
+    }
+
+    #[test]
+    fn test_redundant_actions() {
+        let redundant = statement(r#"["s3:GetObject", "s3:*", "s3:Get*", "ec2:StartInstances"]"#, r#"["*"]"#);
+        assert_eq!(redundant.redundant_actions(), vec![0, 2]);
+
+        let duplicated = statement(r#"["s3:GetObject", "s3:GetObject"]"#, r#"["*"]"#);
+        assert_eq!(duplicated.redundant_actions(), vec![1]);
+
+        let distinct = statement(r#"["s3:GetObject", "s3:PutObject", "s3:List*", "ec2:Describe*"]"#, r#"["*"]"#);
+        assert_eq!(distinct.redundant_actions(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_service_mismatches() {
+        let mismatched = statement(
+            r#"["s3:GetObject", "ec2:StartInstances"]"#,
+            r#"["arn:aws:ec2:us-east-1:123456789012:instance:i-1", "arn:aws:s3:::bucket/*"]"#,
+        );
+        assert_eq!(mismatched.service_mismatches(), vec![(0, 0), (1, 1)]);
+
+        let exempt = statement(
+            r#"["*", "s3:*", "*:GetObject", "S3:GetObject"]"#,
+            r#"["arn:aws:s3:::bucket/*", "arn:aws:*:::thing", "arn:::::"]"#,
+        );
+        assert_eq!(exempt.service_mismatches(), vec![]);
+    }
+
+    #[test]
+    fn test_all_service_actions_against_concrete_resources() {
+        let resources = r#"["arn:aws:s3:::bucket/*", "arn:aws:ec2:us-east-1:123456789012:instance:i-1"]"#;
+        let object = ResourceAbstract::from_str("arn:aws:s3:::bucket/key").unwrap();
+        let instance = ResourceAbstract::from_str("arn:aws:ec2:us-east-1:123456789012:instance:i-1").unwrap();
+
+        for wildcard in ["*", "*:*"] {
+            let everything = statement(&format!(r#"["{wildcard}"]"#), resources);
+            assert_eq!(everything.service_mismatches(), vec![], "{wildcard}");
+            assert_eq!(everything.matches(&WildString("s3:GetObject".to_string()), &object), MaybeEffect::Allow);
+            assert_eq!(everything.matches(&WildString("ec2:StartInstances".to_string()), &instance), MaybeEffect::Allow);
+        }
+
+        let mixed = statement(r#"["*:*", "s3:GetObject"]"#, resources);
+        assert_eq!(mixed.service_mismatches(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_mixed_resource_array_imports() {
+        let statement = statement(r#"["s3:GetObject"]"#, r#"["arn:aws:s3:::b1", "arn:aws:s3:::b1/*", "*"]"#);
+        assert_eq!(statement.resources.len(), 3);
+        assert_eq!(statement.resources[2], ResourceAbstract::from_str("arn:::::").unwrap());
+
+        let action = WildString("s3:GetObject".to_string());
+        let elsewhere = ResourceAbstract::from_str("arn:aws:ec2:us-east-1:123456789012:instance:i-1").unwrap();
+        assert_eq!(statement.matches(&action, &elsewhere), MaybeEffect::Allow);
+
+        let json = serde_json::to_value(&statement).unwrap();
+        assert_eq!(json["resources"][2], "*");
+    }
+}
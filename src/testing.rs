@@ -0,0 +1,73 @@
+//! Helpers for testing custom engines.
+//!
+//! This module is available with the `testing` feature and is intended for
+//! downstream crates implementing their own `EngineTrait` types.
+
+use std::fmt::Debug;
+use std::str::FromStr;
+
+/// Asserts that every sample string round-trips through `FromStr` and `ToString`.
+///
+/// For each sample `s`, this checks that `T::from_str(s)` succeeds, that the
+/// parsed value renders back to exactly `s`, and that parsing the rendered
+/// string yields the same value again. Samples should therefore be written in
+/// their canonical form (e.g. `"us-east-1"` rather than `"US East (N. Virginia)"`).
+///
+/// A mismatch here usually means `FromStr` and `ToString` disagree, which
+/// silently corrupts resources when they are serialized and parsed again.
+///
+/// # Panics
+/// Panics with the offending sample if any of the checks fail.
+///
+/// # Examples
+/// ```
+/// use rust_iam::aws::WildString;
+/// use rust_iam::testing::assert_roundtrip;
+///
+/// assert_roundtrip::<WildString>(&["s3:GetObject", "s3:*"]);
+/// ```
+pub fn assert_roundtrip<T>(samples: &[&str])
+where
+    T: FromStr + ToString + PartialEq + Debug,
+    T::Err: Debug,
+{
+    for sample in samples {
+        let parsed = T::from_str(sample)
+            .unwrap_or_else(|e| panic!("failed to parse {sample:?}: {e:?}"));
+        let rendered = parsed.to_string();
+        assert_eq!(&rendered, sample, "{sample:?} parsed as {parsed:?} but renders as {rendered:?}");
+
+        let reparsed = T::from_str(&rendered)
+            .unwrap_or_else(|e| panic!("failed to re-parse {rendered:?}: {e:?}"));
+        assert_eq!(reparsed, parsed, "{rendered:?} does not parse back to {parsed:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::{AwsPartition, AwsRegion, WildString};
+
+    #[test]
+    fn test_wild_string_roundtrip() {
+        assert_roundtrip::<WildString>(&["s3:GetObject", "s3:*", "arn-like/path"]);
+    }
+
+    #[test]
+    fn test_region_roundtrip() {
+        assert_roundtrip::<AwsRegion>(&[
+            "us-east-2", "us-east-1", "us-west-1", "us-west-2", "af-south-1", "ap-east-1",
+            "ap-south-2", "ap-southeast-3", "ap-southeast-5", "ap-southeast-4", "ap-south-1",
+            "ap-northeast-3", "ap-northeast-2", "ap-southeast-1", "ap-southeast-2",
+            "ap-northeast-1", "ca-central-1", "ca-west-1", "eu-central-1", "eu-west-1",
+            "eu-west-2", "eu-south-1", "eu-west-3", "eu-south-2", "eu-north-1", "eu-central-2",
+            "il-central-1", "me-south-1", "me-central-1", "sa-east-1", "us-gov-east-1",
+            "us-gov-west-1",
+        ]);
+    }
+
+    #[test]
+    fn test_partition_roundtrip() {
+        assert_roundtrip::<AwsPartition>(&["aws", "aws-cn", "aws-us-gov"]);
+    }
+}
@@ -2,6 +2,18 @@ pub trait MatchesTrait<T> {
     fn matches(&self, value: &Self) -> Result<T, &'static str>;
 }
 
+/// Optional segments, with `self` as the pattern: an unset pattern matches anything, while a
+/// set pattern never matches an unset value.
+impl<T: MatchesTrait<bool>> MatchesTrait<bool> for Option<T> {
+    fn matches(&self, value: &Self) -> Result<bool, &'static str> {
+        match (self, value) {
+            (None, _) => Ok(true),
+            (Some(_), None) => Ok(false),
+            (Some(pattern), Some(value)) => pattern.matches(value),
+        }
+    }
+}
+
 impl MatchesTrait<bool> for usize {
     fn matches(&self, value: &Self) -> Result<bool, &'static str> {
         Ok(self == value)
@@ -12,3 +24,23 @@ impl MatchesTrait<bool> for String {
         Ok(self == value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_option_none_pattern_matches_anything() {
+        let any: Option<String> = None;
+        assert_eq!(any.matches(&None), Ok(true));
+        assert_eq!(any.matches(&Some("value".to_string())), Ok(true));
+    }
+
+    #[test]
+    fn test_option_some_pattern_needs_a_matching_value() {
+        let pattern = Some("value".to_string());
+        assert_eq!(pattern.matches(&None), Ok(false));
+        assert_eq!(pattern.matches(&Some("value".to_string())), Ok(true));
+        assert_eq!(pattern.matches(&Some("other".to_string())), Ok(false));
+    }
+}
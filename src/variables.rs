@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use crate::error::IamError;
+
+/// What to do with a `${...}` placeholder that has no value during substitution.
+///
+/// # Variants
+/// - `Error`: Fail with `IamError::Variable`. This is the default, since a policy that
+///   silently keeps a placeholder rarely does what its author meant.
+/// - `Keep`: Leave the placeholder text in place. It then only matches requests that
+///   contain the literal placeholder.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownVariable {
+    /// Unknown variables are an error.
+    #[default]
+    Error,
+
+    /// Unknown variables are left as written.
+    Keep,
+}
+
+/// Replaces `${name}` placeholders in `text` with values from `variables`.
+///
+/// Substituted values have the wildcard metasymbols `*`, `?` and `\` escaped, so a value can
+/// never widen a pattern. The AWS special forms `${*}`, `${?}` and `${$}` produce a literal
+/// `*`, `?` and `$`. A `$` that does not start a complete `${...}` is kept as written.
+///
+/// Values may not contain `:`, since that would shift the ARN segments after them.
+pub(crate) fn substitute(text: &str, variables: &HashMap<String, String>, unknown: UnknownVariable) -> Result<String, IamError> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            output.push_str(&rest[start..]);
+            return Ok(output);
+        };
+        let name = &after[..end];
+        match name {
+            "*" => output.push_str("\\*"),
+            "?" => output.push_str("\\?"),
+            "$" => output.push('$'),
+            _ => match variables.get(name) {
+                Some(value) if value.contains(':') => {
+                    return Err(IamError::Variable { name: name.to_string(), reason: "value contains ':'" });
+                }
+                Some(value) => {
+                    for c in value.chars() {
+                        if matches!(c, '*' | '?' | '\\') {
+                            output.push('\\');
+                        }
+                        output.push(c);
+                    }
+                }
+                None if unknown == UnknownVariable::Keep => output.push_str(&rest[start..start + 3 + end]),
+                None => return Err(IamError::Variable { name: name.to_string(), reason: "no value supplied" }),
+            },
+        }
+        rest = &after[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<String, String> {
+        HashMap::from([("aws:username".to_string(), "alice".to_string()), ("team".to_string(), "a*b".to_string())])
+    }
+
+    #[test]
+    fn test_substitute_leaves_literal_text_intact() {
+        assert_eq!(substitute("home/${aws:username}/*", &vars(), UnknownVariable::Error).unwrap(), "home/alice/*");
+        assert_eq!(substitute("cost $5 ${team}", &vars(), UnknownVariable::Error).unwrap(), "cost $5 a\\*b");
+        assert_eq!(substitute("${*}${?}${$}", &vars(), UnknownVariable::Error).unwrap(), "\\*\\?$");
+        assert_eq!(substitute("open ${aws:username", &vars(), UnknownVariable::Error).unwrap(), "open ${aws:username");
+    }
+
+    #[test]
+    fn test_unknown_variables() {
+        let err = substitute("${aws:userid}", &vars(), UnknownVariable::Error).unwrap_err();
+        assert_eq!(err, IamError::Variable { name: "aws:userid".to_string(), reason: "no value supplied" });
+        assert_eq!(substitute("a/${aws:userid}/b", &vars(), UnknownVariable::Keep).unwrap(), "a/${aws:userid}/b");
+    }
+}
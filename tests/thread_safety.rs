@@ -0,0 +1,106 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+
+use rust_iam::aws::{AwsEngine, WildString};
+use rust_iam::{Effect, Policy, PolicyCollection, PreparedStatement, ResourceAbstract, SharedPolicies, Statement};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_core_types_are_send_and_sync() {
+    assert_send_sync::<AwsEngine>();
+    assert_send_sync::<Effect>();
+    assert_send_sync::<ResourceAbstract<AwsEngine>>();
+    assert_send_sync::<Statement<AwsEngine>>();
+    assert_send_sync::<PreparedStatement<AwsEngine>>();
+    assert_send_sync::<Policy<AwsEngine>>();
+    assert_send_sync::<PolicyCollection<AwsEngine>>();
+    assert_send_sync::<SharedPolicies<AwsEngine>>();
+}
+
+#[test]
+fn test_policy_collection_shared_across_threads() {
+    let policy: Policy<AwsEngine> = serde_json::from_str(
+        r#"{
+            "name": "bucket-readers",
+            "statements": [
+                {"effect": "allow", "actions": ["s3:Get*"], "resources": ["arn:aws:s3:us-east-1:123456789012:bucket:*"]},
+                {"effect": "deny", "actions": ["s3:GetSecret"], "resources": ["arn:aws:s3:us-east-1:123456789012:bucket:*"]}
+            ]
+        }"#,
+    )
+    .unwrap();
+    let collection = Arc::new(PolicyCollection(vec![policy]));
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let collection = Arc::clone(&collection);
+            thread::spawn(move || {
+                let resource =
+                    ResourceAbstract::<AwsEngine>::from_str(&format!("arn:aws:s3:us-east-1:123456789012:bucket:b{i}"))
+                        .unwrap();
+                (0..100).all(|_| {
+                    collection.validate(&WildString("s3:GetObject".to_string()), &resource)
+                        && !collection.validate(&WildString("s3:GetSecret".to_string()), &resource)
+                        && !collection.validate(&WildString("s3:PutObject".to_string()), &resource)
+                })
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert!(handle.join().unwrap());
+    }
+}
+
+#[test]
+fn test_shared_policies_reload_while_reading() {
+    let readers_only: PolicyCollection<AwsEngine> = serde_json::from_str(
+        r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:GetObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}]"#,
+    )
+    .unwrap();
+    let readers_and_writers: PolicyCollection<AwsEngine> = serde_json::from_str(
+        r#"[{"statements": [
+            {"effect": "allow", "actions": ["s3:GetObject", "s3:PutObject"], "resources": ["arn:aws:s3:::bucket/*"]}
+        ]}]"#,
+    )
+    .unwrap();
+    let shared = SharedPolicies::new(readers_only.clone());
+    let resource = ResourceAbstract::<AwsEngine>::from_str("arn:aws:s3:::bucket/key").unwrap();
+    let get = WildString("s3:GetObject".to_string());
+    let put = WildString("s3:PutObject".to_string());
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let (shared, resource, get, put) = (shared.clone(), resource.clone(), get.clone(), put.clone());
+            thread::spawn(move || {
+                for _ in 0..2000 {
+                    // Both sets allow reads, so a reader must never see a denied read mid-swap.
+                    assert!(shared.validate(&get, &resource));
+                    // Within one snapshot, an allowed write implies an allowed read.
+                    assert!(shared.read(|p| !p.validate(&put, &resource) || p.validate(&get, &resource)));
+                }
+            })
+        })
+        .collect();
+
+    let writer = {
+        let shared = shared.clone();
+        thread::spawn(move || {
+            for i in 0..200 {
+                let next = if i % 2 == 0 { readers_and_writers.clone() } else { readers_only.clone() };
+                shared.replace(next);
+            }
+            shared.update(|policies| *policies = readers_and_writers);
+        })
+    };
+
+    writer.join().unwrap();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert!(shared.validate(&put, &resource));
+}
@@ -0,0 +1,29 @@
+use std::str::FromStr;
+
+use rust_iam::aws::{AwsEngine, WildString};
+use rust_iam::traits::MatchesTrait;
+use rust_iam::{Policy, PolicyCollection, ResourceAbstract, ResourceRef};
+
+const POLICY: &str = r#"{
+    "name": "invokers",
+    "statements": [
+        {"effect": "allow", "actions": ["lambda:InvokeFunction"], "resources": ["arn:aws:lambda:us-east-1:123456789012:function:my-fn:prod"]}
+    ]
+}"#;
+
+const WILDCARD_REQUEST: &str = "arn:aws:lambda:us-east-1:123456789012:function:my-fn:*";
+
+#[test]
+fn test_wildcard_requests_are_evaluated_without_panicking() {
+    let policy: Policy<AwsEngine> = serde_json::from_str(POLICY).unwrap();
+    let collection = PolicyCollection(vec![policy]);
+    let action = WildString("lambda:InvokeFunction".to_string());
+    let request = ResourceAbstract::<AwsEngine>::from_str(WILDCARD_REQUEST).unwrap();
+
+    assert!(!collection.validate(&action, &request));
+    assert_eq!(collection.validate_request(&action, &request, false), Ok(false));
+
+    let pattern = ResourceRef::<AwsEngine>::parse("arn:aws:lambda:us-east-1:123456789012:function:my-fn:prod").unwrap();
+    let request = ResourceRef::<AwsEngine>::parse(WILDCARD_REQUEST).unwrap();
+    assert_eq!(pattern.matches(&request), Ok(false));
+}